@@ -0,0 +1,228 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Headless client for scripting games and debugging without the window. Uses
+//! the session stored by the game, so log in through the game first.
+
+use std::str::FromStr;
+
+use quad_storage::STORAGE;
+use sui_crypto::ed25519::Ed25519PrivateKey;
+use sui_rpc::Client;
+use sui_sdk_types::{Address, TransactionEffectsV2, TransactionEvents};
+use tokio::runtime::Runtime;
+
+use commander::{
+    SESSION_KEY, Session,
+    config::GRPC_URL,
+    init_storage_dir,
+    sui::{fetch::GameClient, tx::TxExecutor},
+    types::{GridPath, History, manhattan_distance},
+};
+
+const USAGE: &str = "Usage: commander-cli <command> [args]
+
+Commands:
+    presets                         List published presets
+    recruits [address]              List recruits of the address or the session
+    replays [address]               List replays of the address or the session
+    game <game>                     Print the board and the history of a game
+    move <game> <x,y> <x,y>...      Move the unit along the path
+    reload <game> <x,y>             Reload the unit
+    attack <game> <x,y> <x,y>       Attack the target with the unit
+    grenade <game> <x,y> <x,y>      Throw a grenade at the target
+    next-turn <game>                End the turn
+    quit <game>                     Destroy the game and save the replay";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.is_empty() {
+        println!("{}", USAGE);
+        return;
+    }
+
+    init_storage_dir();
+
+    let default_provider = rustls::crypto::aws_lc_rs::default_provider();
+    rustls::crypto::CryptoProvider::install_default(default_provider).unwrap();
+
+    let rt = Runtime::new().unwrap();
+    if let Err(err) = rt.block_on(run(args)) {
+        eprintln!("Error: {}\n\n{}", err, USAGE);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Vec<String>) -> Result<(), anyhow::Error> {
+    let client = Client::new(GRPC_URL)?;
+    let mut game_client = GameClient::new(client.clone());
+    let (command, args) = args.split_first().unwrap();
+
+    match (command.as_str(), args) {
+        ("presets", []) => {
+            for preset in game_client.list_presets().await {
+                let preset = preset.data;
+                println!(
+                    "{} {} ({}x{}, {} spawns)",
+                    preset.id,
+                    preset.name,
+                    preset.map.rows(),
+                    preset.map.cols(),
+                    preset.positions.len()
+                );
+            }
+        }
+        ("recruits", args) => {
+            let address = address_or_session(args)?;
+            for recruit in game_client.list_recruits(address).await {
+                let recruit = recruit.data;
+                println!(
+                    "{} {} ({}) weapon: {}, armor: {}",
+                    recruit.id.0,
+                    recruit.metadata.name,
+                    recruit.rank,
+                    recruit.weapon.as_ref().map_or("-", |w| w.name.as_str()),
+                    recruit.armor.as_ref().map_or("-", |a| a.name.as_str()),
+                );
+            }
+        }
+        ("replays", args) => {
+            let address = address_or_session(args)?;
+            for replay in game_client.list_replays(address).await {
+                let replay = replay.data;
+                println!(
+                    "{} preset: {} ({} records)",
+                    replay.id.0,
+                    replay.preset_id.0,
+                    replay.history.0.len()
+                );
+            }
+        }
+        ("game", [game_id]) => {
+            let game = game_client.get_game(Address::from_str(game_id)?).await?;
+            println!("Turn: {}, players: {}", game.map.turn, game.players.len());
+            print!("{}", game.map.to_text().coordinates(true));
+            print_history(&game.history);
+        }
+        ("move", [game_id, path @ ..]) if path.len() > 1 => {
+            let path = path
+                .iter()
+                .map(|p| parse_position(p))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if path.windows(2).any(|w| manhattan_distance(w[0], w[1]) != 1) {
+                return Err(anyhow::anyhow!("Path must be made of adjacent tiles"));
+            }
+
+            let mut tx = executor(client).await?;
+            print_result(
+                tx.move_unit(Address::from_str(game_id)?, GridPath::new(path))
+                    .await?,
+            );
+        }
+        ("reload", [game_id, unit]) => {
+            let mut tx = executor(client).await?;
+            print_result(
+                tx.perform_reload(Address::from_str(game_id)?, parse_position(unit)?)
+                    .await?,
+            );
+        }
+        ("attack", [game_id, unit, target]) => {
+            let mut tx = executor(client).await?;
+            print_result(
+                tx.perform_attack(
+                    Address::from_str(game_id)?,
+                    parse_position(unit)?,
+                    parse_position(target)?,
+                )
+                .await?,
+            );
+        }
+        ("grenade", [game_id, unit, target]) => {
+            let mut tx = executor(client).await?;
+            print_result(
+                tx.perform_grenade(
+                    Address::from_str(game_id)?,
+                    parse_position(unit)?,
+                    parse_position(target)?,
+                )
+                .await?,
+            );
+        }
+        ("next-turn", [game_id]) => {
+            let mut tx = executor(client).await?;
+            print_result(tx.next_turn(Address::from_str(game_id)?).await?);
+        }
+        ("quit", [game_id]) => {
+            let mut tx = executor(client).await?;
+            print_result(tx.quit_game(Address::from_str(game_id)?).await?);
+        }
+        _ => return Err(anyhow::anyhow!("Unknown command or arguments: {}", command)),
+    }
+
+    Ok(())
+}
+
+/// Read the session stored by the game.
+fn load_session() -> Result<Session, anyhow::Error> {
+    let session = STORAGE
+        .lock()
+        .unwrap()
+        .get(SESSION_KEY)
+        .ok_or(anyhow::anyhow!("No session found, log in through the game"))?;
+
+    Ok(serde_json::from_str(&session)?)
+}
+
+/// Create the `TxExecutor` from the stored session, fails if it's expired.
+async fn executor(client: Client) -> Result<TxExecutor, anyhow::Error> {
+    let session = load_session()?;
+    let epoch = GameClient::new(client.clone()).get_epoch().await?;
+
+    if session.max_epoch < epoch {
+        return Err(anyhow::anyhow!("Session expired, log in through the game"));
+    }
+
+    Ok(TxExecutor::new(
+        Ed25519PrivateKey::from_pem(&session.keypair)?,
+        session.zkp,
+        session.max_epoch,
+        client,
+    ))
+}
+
+/// The address passed as an argument or the address of the session.
+fn address_or_session(args: &[String]) -> Result<Address, anyhow::Error> {
+    match args {
+        [address] => Ok(Address::from_str(address)?),
+        [] => Ok(load_session()?.address),
+        _ => Err(anyhow::anyhow!("Expected at most one address")),
+    }
+}
+
+/// Parse a position in the `x,y` format.
+fn parse_position(arg: &str) -> Result<(u8, u8), anyhow::Error> {
+    let (x, y) = arg
+        .split_once(',')
+        .ok_or(anyhow::anyhow!("Invalid position: {}, expected x,y", arg))?;
+
+    Ok((x.trim().parse()?, y.trim().parse()?))
+}
+
+fn print_history(history: &History) {
+    for record in history.0.iter() {
+        println!("{}", record);
+    }
+}
+
+/// Print the status of the transaction and the records it emitted.
+fn print_result((effects, events): (TransactionEffectsV2, Option<TransactionEvents>)) {
+    println!("Status: {:?}", effects.status);
+
+    for event in events.iter().flat_map(|events| events.0.iter()) {
+        // All events emitted by the game transactions are `History` records.
+        if let Ok(history) = bcs::from_bytes::<History>(event.contents.as_ref()) {
+            print_history(&history);
+        }
+    }
+}
@@ -21,6 +21,12 @@ pub const ARMOR_STRUCT_TAG: &'static str =
     "0x6e6770b3554b6bf4997aee770b29e1395aa640979afc84504058f05721ee54a7::armor::Armor";
 pub const PRESET_STRUCT_TAG: &'static str =
     "0x6e6770b3554b6bf4997aee770b29e1395aa640979afc84504058f05721ee54a7::commander::Preset";
+pub const HOST_STRUCT_TAG: &str =
+    "0x6e6770b3554b6bf4997aee770b29e1395aa640979afc84504058f05721ee54a7::commander::Host";
+
+/// Endpoint of the Sui fullnode used by the game and the CLI.
+pub const GRPC_URL: &str =
+    "https://fullnode.testnet.sui.io/sui.rpc.v2beta2.LiveDataService/SimulateTransaction";
 
 // === Draw Configuration ===
 
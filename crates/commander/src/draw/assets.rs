@@ -31,7 +31,7 @@ thread_local! {
 /// - enum + row list
 ///
 /// Use:
-/// ```rust
+/// ```ignore
 /// load_and_register_sprite!(self.sprites, "soldier.png", 6, 32.0 * 4.0, [
 ///     (Sprite::SoldierRunDown, 0),
 ///     (Sprite::SoldierRunRight, 1),
@@ -290,6 +290,12 @@ impl AssetStore {
     }
 }
 
+impl Default for AssetStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub trait Asset {
     type AssetType;
 
@@ -96,9 +96,10 @@ pub enum ZIndex {
     BlackBackground = 0,
     Background = 1,
     Grid = 2,
-    TopCover = 3,
-    Highlight = 4,
-    Cursor = 5,
+    Fog = 3,
+    TopCover = 4,
+    Highlight = 5,
+    Cursor = 6,
     UnitShadow = 10,
     Unit = 11,
     Obstacle = 20,
@@ -174,7 +175,7 @@ pub fn draw_highlight(highlight: &Highlight, dimensions: (u8, u8)) {
 
 /// Draw a line through the given tiles, line starts at the center of each tile
 /// and goes to the center of the next tile.
-pub fn draw_path(path: &[(u8, u8)], dimensions: (u8, u8)) {
+pub fn draw_path(path: &[(u8, u8)], dimensions: (u8, u8), color: Color) {
     let (scale_x, scale_y) = get_scale(dimensions);
     let thickness = 3.0;
 
@@ -191,7 +192,7 @@ pub fn draw_path(path: &[(u8, u8)], dimensions: (u8, u8)) {
                 (y2 as f32 + 0.5) * TILE_SIZE * scale_y - thickness / 2.0 + MAP_PADDING,
                 (x2 as f32 + 0.5) * TILE_SIZE * scale_x + thickness / 2.0 + MAP_PADDING,
             )
-            .color(BLUE)
+            .color(color)
             .thickness(thickness)
             .z_index(ZIndex::Highlight)
             .ignore_padding()
@@ -622,6 +623,12 @@ impl DrawRectangleLinesBuilder {
     }
 }
 
+impl Default for DrawRectangleLinesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct DrawRectangleBuilder {
     x: Option<f32>,
     y: Option<f32>,
@@ -714,6 +721,12 @@ impl DrawRectangleBuilder {
     }
 }
 
+impl Default for DrawRectangleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct DrawLineBuilder {
     x1: Option<f32>,
     y1: Option<f32>,
@@ -794,6 +807,12 @@ impl DrawLineBuilder {
     }
 }
 
+impl Default for DrawLineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct DrawTextBuilder {
     text: String,
     x: Option<f32>,
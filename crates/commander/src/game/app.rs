@@ -3,6 +3,7 @@
 
 use std::{
     collections::HashMap,
+    path::Path,
     sync::{Arc, Mutex, mpsc::Sender},
 };
 
@@ -14,12 +15,13 @@ use crate::{
     Message as TokioMessage, State, WithRef,
     draw::*,
     game::{
-        Editor, EditorMessage, SettingsScreen, SettingsScreenMessage,
+        BarracksMessage, BarracksScreen, Editor, EditorMessage, LoadoutAction, Report,
+        ReportMessage, ReportScreen, SettingsScreen, SettingsScreenMessage,
         play::{Play, PlayMessage},
     },
     input::InputCommand,
     sound::Effect,
-    types::{Game, ID, Preset, Recruit, Replay},
+    types::{Game, Host, ID, Map, Preset, REPLAYS_DIR, Recruit, Replay, ReplayFile, Roster},
 };
 
 pub struct App {
@@ -39,8 +41,10 @@ pub struct App {
 pub enum Message {
     /// Prepare to open a login window.
     PrepareLogin,
-    /// Start a new game.
-    StartGame,
+    /// Start a new game from the preset with the given ID and the owned
+    /// recruits assigned to its spawns, or resume the stored active game if
+    /// there's no preset.
+    StartGame(Option<ID>, Vec<Option<ID>>),
     /// Logout the user.
     Logout,
     /// Fetch the list of presets.
@@ -49,6 +53,30 @@ pub enum Message {
     FetchRecruits,
     /// Fetch the list of replays.
     FetchReplays,
+    /// Find the recruits who took part in the game of the replay.
+    FetchRoster(Replay),
+    /// Write the replay with its preset and roster to the storage dir.
+    ExportReplay(Replay),
+    /// Fetch the list of games open for joining.
+    FetchHostedGames,
+    /// Fetch the game with the given ID to show its spawns before joining.
+    FetchGame(ID),
+    /// Host a multiplayer game from the preset with the given ID and the
+    /// owned recruits assigned to the host's spawns.
+    HostGame(ID, Vec<Option<ID>>),
+    /// Join a game hosted by another player with the owned recruits assigned
+    /// to the spawns left by the host.
+    JoinGame(WithRef<Host>, Vec<Option<ID>>),
+    /// Hire a new recruit.
+    HireRecruit,
+    /// Dismiss the owned recruit with the given ID.
+    DismissRecruit(ID),
+    /// Fetch the owned weapons and armor which are not equipped.
+    FetchInventory,
+    /// Change the loadout of the owned recruit with the given ID.
+    UpdateLoadout(ID, LoadoutAction),
+    /// Publish a preset created in the editor.
+    PublishPreset(Preset),
     /// Send a PlayMessage to the tokio runtime.
     Play(PlayMessage),
 }
@@ -66,10 +94,98 @@ pub enum Screen {
     Play(Play),
     /// Show list of replays.
     Replays(Menu<ReplayMenuItem>),
+    /// Show the replay files exported to the storage dir.
+    ReplayFiles(Menu<ReplayMenuItem>),
+    /// Show the list of presets to start a new game with.
+    Presets(Menu<PresetMenuItem>),
+    /// Show the squad selection for the game to start, host or join.
+    Squad(SquadScreen),
+    /// Show the multiplayer lobby: hosted games and presets to host.
+    Lobby(Menu<LobbyMenuItem>),
     /// Play a replay.
     Replay(Player),
     /// Show settings menu.
     Settings(SettingsScreen),
+    /// Show owned recruits.
+    Barracks(BarracksScreen),
+    /// Show the after-action report of a game.
+    Report(ReportScreen),
+}
+
+#[derive(Debug, Clone)]
+/// Squad selection before starting, hosting or joining a game. Owned recruits
+/// are assigned to the spawns, the spawns left empty get new recruits.
+pub struct SquadScreen {
+    pub menu: Menu<SquadMenuItem>,
+    pub mode: SquadMode,
+    /// Map of the game, `None` until the game to join is fetched.
+    pub map: Option<Map>,
+    /// Spawns of the player in the order `commander::place_recruit` takes
+    /// them, which is from the back of the positions.
+    pub spawns: Vec<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SquadMode {
+    /// Single player game from the preset with the given ID.
+    Start(ID),
+    /// Multiplayer game hosted from the preset with the given ID.
+    Host(ID),
+    /// Game hosted by another player.
+    Join(Box<WithRef<Host>>),
+}
+
+impl SquadScreen {
+    /// Squad for a game from the `preset`. The host of a multiplayer game
+    /// fills the first half of the spawns.
+    pub fn preset(recruits: &[WithRef<Recruit>], preset: &WithRef<Preset>, host: bool) -> Self {
+        let mut spawns = preset
+            .data
+            .positions
+            .iter()
+            .rev()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mode = if host {
+            spawns.truncate(spawns.len().div_ceil(2));
+            SquadMode::Host(preset.data.id)
+        } else {
+            SquadMode::Start(preset.data.id)
+        };
+
+        Self {
+            menu: Menu::squad(recruits, &[]),
+            mode,
+            map: Some(preset.data.map.clone()),
+            spawns,
+        }
+    }
+
+    /// Squad for joining the game of the `host`, the spawns are known once
+    /// the game is fetched, see `set_game`.
+    pub fn join(recruits: &[WithRef<Recruit>], host: WithRef<Host>) -> Self {
+        Self {
+            menu: Menu::squad(recruits, &[]),
+            mode: SquadMode::Join(Box::new(host)),
+            map: None,
+            spawns: Vec::new(),
+        }
+    }
+
+    /// Take the spawns left by the host of the fetched `game`.
+    pub fn set_game(&mut self, game: &Game) {
+        if let SquadMode::Join(host) = &self.mode
+            && host.data.game_id == game.id
+        {
+            self.map = Some(game.map.clone());
+            self.spawns = game.positions.iter().rev().cloned().collect();
+        }
+    }
+
+    /// Owned recruit assigned to each of the spawns.
+    pub fn assigned_recruits(&self) -> Vec<Option<ID>> {
+        self.menu.assigned_recruits(self.spawns.len())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -143,22 +259,34 @@ impl App {
             TokioMessage::LoginStarted => self.screen = Screen::Login,
             TokioMessage::LoginFinished => self.reload_screen(),
             TokioMessage::GameDestroyed => {
-                self.screen = Screen::MainMenu(Menu::main(self.state.lock().unwrap().address));
+                // The report of the quit game stays on the screen.
+                if !matches!(self.screen, Screen::Report(_)) {
+                    self.screen = Screen::MainMenu(Menu::main(self.state.lock().unwrap().address));
+                }
             }
             TokioMessage::PlayEffects(effects) => match &mut self.screen {
                 Screen::Play(play) => play.apply_effects(effects),
                 _ => {}
             },
+            TokioMessage::HostedGame(game) => {
+                if let Screen::Squad(squad) = &mut self.screen {
+                    squad.set_game(&game);
+                }
+            }
+            TokioMessage::ReplayRoster(roster) => match &mut self.screen {
+                Screen::Replay(player) => player
+                    .set_roster(roster)
+                    .unwrap_or_else(|e| eprintln!("Error: {}", e)),
+                Screen::Report(report) => report.set_roster(roster),
+                _ => {}
+            },
             TokioMessage::GameStarted => {
-                self.screen = Screen::Play(Play::from(
-                    self.state
-                        .lock()
-                        .as_ref()
-                        .unwrap()
-                        .active_game
-                        .clone()
-                        .unwrap(),
-                ));
+                let state = self.state.lock().unwrap();
+                let mut play = Play::from(state.active_game.clone().unwrap());
+                play.set_owners(state.address, &state.game_recruits);
+                drop(state);
+
+                self.screen = Screen::Play(play);
             }
         }
     }
@@ -176,29 +304,38 @@ impl App {
                     self.screen = Screen::MainMenu(Menu::main(state.address));
                 }
                 EditorMessage::Play(preset) => {
-                    let mut play = Play::from(Game::from(preset.clone()));
-                    play.test_preset = Some(preset.clone());
-                    self.screen = Screen::Play(play);
+                    self.screen = Screen::Play(Play::new_test(preset.clone()));
                 }
+                EditorMessage::Publish(preset) => self.send_message(Message::PublishPreset(preset)),
                 EditorMessage::None => {}
             },
             Screen::Play(play) => match play.handle_key_press(key) {
                 PlayMessage::Exit => self.screen = Screen::MainMenu(Menu::main(state.address)),
-                PlayMessage::NextTurn if play.test_preset.is_none() => {
-                    self.send_message(Message::Play(PlayMessage::NextTurn));
-                }
                 PlayMessage::QuitGame if play.test_preset.is_none() => {
                     self.send_message(Message::Play(PlayMessage::QuitGame));
-                    self.screen = Screen::MainMenu(Menu::main(state.address))
+                    self.screen = match &state.active_game {
+                        Some(game) => Screen::Report(ReportScreen::new(
+                            Report::from_history(&game.history),
+                            Roster::from_game(game, state.game_recruits.clone()),
+                        )),
+                        None => Screen::MainMenu(Menu::main(state.address)),
+                    };
                 }
-                m @ (PlayMessage::Move(_) | PlayMessage::Attack(_, _) | PlayMessage::Reload(_)) => {
+                m @ (PlayMessage::Move(_)
+                | PlayMessage::Dash(_, _)
+                | PlayMessage::Attack(_, _)
+                | PlayMessage::Reload(_)
+                | PlayMessage::Grenade(_, _)
+                | PlayMessage::NextTurn) => {
                     if play.test_preset.is_none() {
                         self.send_message(Message::Play(m));
                     } else {
-                        // In test mode, we can reply with a random history
-                        // instead of invoking the network.
-                        let history = play.message_to_random_history(m);
-                        play.apply_effects(history);
+                        // In test mode, the local rules engine replies with
+                        // the history instead of invoking the network.
+                        match play.simulate(m) {
+                            Ok(history) => play.apply_effects(history),
+                            Err(err) => eprintln!("Rules error: {}", err),
+                        }
                     }
                 }
                 PlayMessage::BackToEditor if play.test_preset.is_some() => {
@@ -210,8 +347,16 @@ impl App {
                 PlayerMessage::Exit => {
                     self.screen = Screen::MainMenu(Menu::main(state.address));
                 }
+                PlayerMessage::Report(report) => {
+                    let roster = player.roster.clone();
+                    self.screen = Screen::Report(ReportScreen::new(report, roster));
+                }
                 PlayerMessage::None => {}
             },
+            Screen::Report(report) => match report.handle_key_press(key) {
+                ReportMessage::Exit => self.screen = Screen::MainMenu(Menu::main(state.address)),
+                ReportMessage::None => {}
+            },
             Screen::MainMenu(menu) => match key {
                 InputCommand::Up => menu.previous_item(),
                 InputCommand::Down => menu.next_item(),
@@ -224,10 +369,31 @@ impl App {
                             // Starts the login process.
                             self.send_message(Message::PrepareLogin);
                         }
-                        MainMenuItem::StartGame => {
-                            self.send_message(Message::StartGame);
+                        MainMenuItem::StartGame if has_active_game() => {
+                            self.send_message(Message::StartGame(None, vec![]));
                             self.screen = Screen::CreatingGame;
                         }
+                        MainMenuItem::StartGame => {
+                            self.send_message(Message::FetchPresets);
+                            self.screen = Screen::Presets(Menu::presets(&state.presets))
+                        }
+                        MainMenuItem::Multiplayer => {
+                            self.send_message(Message::FetchHostedGames);
+                            self.screen = Screen::Lobby(Menu::lobby(
+                                &state.presets,
+                                &state.hosted_games,
+                                state.address,
+                            ))
+                        }
+                        MainMenuItem::Barracks => {
+                            self.send_message(Message::FetchRecruits);
+                            self.send_message(Message::FetchInventory);
+                            self.screen = Screen::Barracks(BarracksScreen::new(
+                                &state.recruits,
+                                &state.weapons,
+                                &state.armor,
+                            ))
+                        }
                         MainMenuItem::Replays => {
                             self.send_message(Message::FetchReplays);
                             self.screen = Screen::Replays(Menu::replays(&state.replays))
@@ -243,6 +409,17 @@ impl App {
                 }
                 _ => {}
             },
+            Screen::Barracks(barracks) => match barracks.handle_key_press(key) {
+                BarracksMessage::Exit => {
+                    self.screen = Screen::MainMenu(Menu::main(state.address));
+                }
+                BarracksMessage::Hire => self.send_message(Message::HireRecruit),
+                BarracksMessage::Dismiss(id) => self.send_message(Message::DismissRecruit(id)),
+                BarracksMessage::Loadout(id, action) => {
+                    self.send_message(Message::UpdateLoadout(id, action))
+                }
+                BarracksMessage::None => {}
+            },
             Screen::Settings(menu) => match menu.handle_key_press(key) {
                 SettingsScreenMessage::Exit => {
                     self.screen = Screen::MainMenu(Menu::main(state.address));
@@ -267,15 +444,145 @@ impl App {
                     Effect::Tada.play();
                     match menu.selected_item() {
                         ReplayMenuItem::Replay(replay) => {
-                            self.screen = Screen::Replay(Player::new(replay.data.clone()));
+                            let replay = replay.data.clone();
                             self.send_message(Message::FetchPresets);
+                            self.send_message(Message::FetchRoster(replay.clone()));
+                            self.screen = Screen::Replay(Player::new(replay));
                         }
+                        ReplayMenuItem::OpenFile => {
+                            let files = ReplayFile::list(Path::new(REPLAYS_DIR));
+                            self.screen = Screen::ReplayFiles(Menu::replay_files(files));
+                        }
+                        ReplayMenuItem::File(_) => {}
                         ReplayMenuItem::Back => {
                             Effect::Data.play();
                             self.screen = Screen::MainMenu(Menu::main(state.address))
                         }
                     }
                 }
+                InputCommand::Tool => {
+                    if let ReplayMenuItem::Replay(replay) = menu.selected_item() {
+                        let replay = replay.data.clone();
+                        let report = Report::from_history(&replay.history);
+                        self.send_message(Message::FetchRoster(replay));
+                        self.screen = Screen::Report(ReportScreen::new(report, Roster::default()));
+                    }
+                }
+                InputCommand::Action => {
+                    if let ReplayMenuItem::Replay(replay) = menu.selected_item() {
+                        let replay = replay.data.clone();
+                        Effect::Data.play();
+                        self.send_message(Message::ExportReplay(replay));
+                    }
+                }
+                _ => {}
+            },
+            Screen::ReplayFiles(menu) => match key {
+                InputCommand::Up => menu.previous_item(),
+                InputCommand::Down => menu.next_item(),
+                InputCommand::Menu => self.screen = Screen::Replays(Menu::replays(&state.replays)),
+                InputCommand::Select => match menu.selected_item() {
+                    ReplayMenuItem::File(path) => match ReplayFile::load(path) {
+                        Ok(file) => {
+                            Effect::Tada.play();
+                            let mut player = Player::new(file.replay);
+                            player.add_preset(file.preset);
+                            player
+                                .set_roster(file.roster)
+                                .unwrap_or_else(|e| eprintln!("Error: {}", e));
+                            self.screen = Screen::Replay(player);
+                        }
+                        Err(err) => eprintln!("Failed to open {}: {}", path.display(), err),
+                    },
+                    _ => {
+                        Effect::Data.play();
+                        self.screen = Screen::Replays(Menu::replays(&state.replays))
+                    }
+                },
+                _ => {}
+            },
+            Screen::Presets(menu) => match key {
+                InputCommand::Up => menu.previous_item(),
+                InputCommand::Down => menu.next_item(),
+                InputCommand::Menu => self.screen = Screen::MainMenu(Menu::main(state.address)),
+                InputCommand::Select => {
+                    Effect::Tada.play();
+                    match menu.selected_item() {
+                        PresetMenuItem::Preset(preset) => {
+                            self.screen =
+                                Screen::Squad(SquadScreen::preset(&state.recruits, preset, false));
+                            self.send_message(Message::FetchRecruits);
+                        }
+                        PresetMenuItem::Back => {
+                            Effect::Data.play();
+                            self.screen = Screen::MainMenu(Menu::main(state.address))
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Screen::Squad(squad) => {
+                let back = match squad.mode {
+                    SquadMode::Start(_) => Screen::Presets(Menu::presets(&state.presets)),
+                    SquadMode::Host(_) | SquadMode::Join(_) => Screen::Lobby(Menu::lobby(
+                        &state.presets,
+                        &state.hosted_games,
+                        state.address,
+                    )),
+                };
+
+                match key {
+                    InputCommand::Up => squad.menu.previous_item(),
+                    InputCommand::Down => squad.menu.next_item(),
+                    InputCommand::Menu => self.screen = back,
+                    InputCommand::Select => match squad.menu.selected_item() {
+                        SquadMenuItem::Start => {
+                            Effect::Tada.play();
+                            let assigned = squad.assigned_recruits();
+                            let message = match &squad.mode {
+                                SquadMode::Start(id) => Message::StartGame(Some(*id), assigned),
+                                SquadMode::Host(id) => Message::HostGame(*id, assigned),
+                                SquadMode::Join(host) => Message::JoinGame(*host.clone(), assigned),
+                            };
+                            self.screen = Screen::CreatingGame;
+                            self.send_message(message);
+                        }
+                        SquadMenuItem::Recruit(..) => {
+                            squad.menu.assign_next_spawn(squad.spawns.len());
+                        }
+                        SquadMenuItem::Back => {
+                            Effect::Data.play();
+                            self.screen = back;
+                        }
+                    },
+                    _ => {}
+                }
+            }
+            Screen::Lobby(menu) => match key {
+                InputCommand::Up => menu.previous_item(),
+                InputCommand::Down => menu.next_item(),
+                InputCommand::Menu => self.screen = Screen::MainMenu(Menu::main(state.address)),
+                InputCommand::Select => {
+                    Effect::Tada.play();
+                    match menu.selected_item() {
+                        LobbyMenuItem::Host(preset) => {
+                            self.screen =
+                                Screen::Squad(SquadScreen::preset(&state.recruits, preset, true));
+                            self.send_message(Message::FetchRecruits);
+                        }
+                        LobbyMenuItem::Join(host) => {
+                            let game_id = host.data.game_id;
+                            self.screen =
+                                Screen::Squad(SquadScreen::join(&state.recruits, host.clone()));
+                            self.send_message(Message::FetchGame(game_id));
+                            self.send_message(Message::FetchRecruits);
+                        }
+                        LobbyMenuItem::Back => {
+                            Effect::Data.play();
+                            self.screen = Screen::MainMenu(Menu::main(state.address))
+                        }
+                    }
+                }
                 _ => {}
             },
         }
@@ -284,6 +591,8 @@ impl App {
     pub fn tick(&mut self) {
         match &mut self.screen {
             Screen::Settings(settings) => settings.tick(),
+            Screen::Barracks(barracks) => barracks.tick(),
+            Screen::Report(report) => report.tick(),
             Screen::Replay(player) => player.tick(),
             Screen::Play(play) => play.tick(),
             _ => self.draw(),
@@ -302,6 +611,23 @@ impl App {
             Screen::CreatingGame => return,
             Screen::MainMenu(_) | Screen::Login => Screen::MainMenu(Menu::main(state.address)),
             Screen::Replays(_) => Screen::Replays(Menu::replays(&state.replays)),
+            Screen::ReplayFiles(_) | Screen::Report(_) => return,
+            Screen::Presets(_) => Screen::Presets(Menu::presets(&state.presets)),
+            Screen::Barracks(barracks) => {
+                let mut screen = BarracksScreen::new(&state.recruits, &state.weapons, &state.armor);
+                screen.menu.selected_item =
+                    barracks.menu.selected_item.min(screen.menu.items.len() - 1);
+                Screen::Barracks(screen)
+            }
+            Screen::Squad(squad) => Screen::Squad(SquadScreen {
+                menu: Menu::squad(&state.recruits, &squad.assigned_recruits()),
+                ..squad.clone()
+            }),
+            Screen::Lobby(_) => Screen::Lobby(Menu::lobby(
+                &state.presets,
+                &state.hosted_games,
+                state.address,
+            )),
             Screen::Replay(_) | Screen::Play(_) | Screen::Settings(_) | Screen::Editor(_) => return,
         };
 
@@ -322,6 +648,46 @@ impl Draw for App {
                 menu.draw()
             }
             Screen::Replays(menu) => {
+                draw::draw_main_menu_background();
+                menu.draw();
+
+                DrawCommand::text(
+                    "Action to export the selected replay, Tool to show its report".to_string(),
+                )
+                .position(20.0, screen_height() - 40.0)
+                .font_size(20)
+                .color(WHITE)
+                .schedule();
+            }
+            Screen::ReplayFiles(menu) => {
+                draw::draw_main_menu_background();
+                menu.draw()
+            }
+            Screen::Squad(squad) => {
+                draw::draw_main_menu_background();
+                squad.menu.draw();
+
+                let assigned = squad.assigned_recruits();
+                if let Some(map) = &squad.map {
+                    draw_map_preview(map, &squad.spawns, &assigned);
+                }
+
+                DrawCommand::text(format!(
+                    "Assigned: {}/{}\nSelect a recruit to move it to the next free spawn.\nEmpty spawns get new recruits.",
+                    assigned.iter().flatten().count(),
+                    squad.spawns.len()
+                ))
+                .position(20.0, screen_height() - 80.0)
+                .font_size(20)
+                .color(WHITE)
+                .schedule();
+            }
+            Screen::Presets(menu) => {
+                draw::draw_main_menu_background();
+                menu.draw();
+                menu.draw_preview();
+            }
+            Screen::Lobby(menu) => {
                 draw::draw_main_menu_background();
                 menu.draw()
             }
@@ -353,6 +719,8 @@ impl Draw for App {
             Screen::Play(_play) => unreachable!("Play manages its own draw"),
             Screen::Replay(_player) => unreachable!("Player manages its own draw"),
             Screen::Settings(_settings) => unreachable!("Settings manages its own draw"),
+            Screen::Barracks(_barracks) => unreachable!("Barracks manages its own draw"),
+            Screen::Report(_report) => unreachable!("Report manages its own draw"),
         }
     }
 }
@@ -0,0 +1,279 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Display;
+
+use macroquad::prelude::*;
+
+use super::{app::RecruitScreen, loadout::*, menu::*};
+use crate::{
+    WithRef,
+    draw::{Draw, DrawCommand, ZIndex, draw},
+    game::AppComponent,
+    input::InputCommand,
+    sound::Effect,
+    types::{Armor, ID, Recruit, Slot, Stats, Weapon},
+};
+
+/// Names picked at random for hired recruits.
+const RECRUIT_NAMES: [&str; 12] = [
+    "Ash", "Blake", "Casey", "Drew", "Ellis", "Finley", "Gray", "Harper", "Jules", "Kai", "Morgan",
+    "Quinn",
+];
+
+#[derive(Debug, Clone)]
+/// Lists owned recruits, allows hiring new ones and inspecting or dismissing
+/// existing recruits in the `RecruitScreen`.
+pub struct BarracksScreen {
+    pub menu: Menu<BarracksMenuItem>,
+    /// Currently inspected recruit.
+    pub recruit: Option<RecruitScreen>,
+    /// Loadout of the inspected recruit being edited.
+    pub loadout: Option<Box<LoadoutScreen>>,
+    pub recruits: Vec<WithRef<Recruit>>,
+    /// Owned weapons which are not equipped.
+    pub weapons: Vec<WithRef<Weapon>>,
+    /// Owned armor which is not equipped.
+    pub armor: Vec<WithRef<Armor>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum BarracksMenuItem {
+    Hire,
+    Recruit(Box<WithRef<Recruit>>),
+    Back,
+}
+
+#[derive(Debug, Clone)]
+pub enum BarracksMessage {
+    Exit,
+    Hire,
+    Dismiss(ID),
+    /// Change the loadout of the recruit with the given ID.
+    Loadout(ID, LoadoutAction),
+    None,
+}
+
+impl BarracksScreen {
+    pub fn new(
+        recruits: &[WithRef<Recruit>],
+        weapons: &[WithRef<Weapon>],
+        armor: &[WithRef<Armor>],
+    ) -> Self {
+        Self {
+            menu: Menu::barracks(recruits),
+            recruit: None,
+            loadout: None,
+            recruits: recruits.to_vec(),
+            weapons: weapons.to_vec(),
+            armor: armor.to_vec(),
+        }
+    }
+}
+
+/// Pick a random name for a new recruit.
+pub fn random_recruit_name() -> String {
+    let index = ::rand::random::<usize>() % RECRUIT_NAMES.len();
+    RECRUIT_NAMES[index].to_string()
+}
+
+impl AppComponent for BarracksScreen {
+    type Message = BarracksMessage;
+
+    fn handle_key_press(&mut self, key: InputCommand) -> Self::Message {
+        if let Some(loadout) = &mut self.loadout {
+            return match loadout.handle_key_press(key) {
+                LoadoutMessage::Exit => {
+                    self.loadout = None;
+                    BarracksMessage::None
+                }
+                LoadoutMessage::Action(action) => {
+                    let id = loadout.recruit.data.id;
+                    self.loadout = None;
+                    self.recruit = None;
+                    BarracksMessage::Loadout(id, action)
+                }
+                LoadoutMessage::None => BarracksMessage::None,
+            };
+        }
+
+        // Recruit details are open, navigate the sub menu.
+        if let Some(screen) = &mut self.recruit {
+            match key {
+                InputCommand::Up => screen.menu.previous_item(),
+                InputCommand::Down => screen.menu.next_item(),
+                InputCommand::Menu => self.recruit = None,
+                InputCommand::Select => match screen.menu.selected_item() {
+                    RecruitSubMenuItem::Dismiss => {
+                        Effect::Data.play();
+                        let id = screen.recruit.data.id;
+                        self.recruit = None;
+                        return BarracksMessage::Dismiss(id);
+                    }
+                    RecruitSubMenuItem::Weapon => {
+                        self.loadout = Some(Box::new(LoadoutScreen::new(
+                            screen.recruit.clone(),
+                            Slot::Weapon,
+                            &self.recruits,
+                            &self.weapons,
+                            &self.armor,
+                        )));
+                    }
+                    RecruitSubMenuItem::Armor => {
+                        self.loadout = Some(Box::new(LoadoutScreen::new(
+                            screen.recruit.clone(),
+                            Slot::Armor,
+                            &self.recruits,
+                            &self.weapons,
+                            &self.armor,
+                        )));
+                    }
+                    RecruitSubMenuItem::Back => self.recruit = None,
+                    RecruitSubMenuItem::Stats => {}
+                },
+                _ => {}
+            }
+
+            return BarracksMessage::None;
+        }
+
+        match key {
+            InputCommand::Up => self.menu.previous_item(),
+            InputCommand::Down => self.menu.next_item(),
+            InputCommand::Menu => return BarracksMessage::Exit,
+            InputCommand::Select => match self.menu.selected_item() {
+                BarracksMenuItem::Hire => {
+                    Effect::Tada.play();
+                    return BarracksMessage::Hire;
+                }
+                BarracksMenuItem::Recruit(recruit) => {
+                    self.recruit = Some(RecruitScreen {
+                        menu: Menu::recruit_sub(recruit),
+                        recruit: *recruit.clone(),
+                    });
+                }
+                BarracksMenuItem::Back => return BarracksMessage::Exit,
+            },
+            _ => {}
+        }
+
+        BarracksMessage::None
+    }
+
+    fn tick(&mut self) {
+        if let Some(loadout) = &mut self.loadout {
+            return loadout.tick();
+        }
+
+        draw::draw_main_menu_background();
+
+        match &self.recruit {
+            Some(screen) => {
+                screen.menu.draw();
+
+                let recruit = &screen.recruit.data;
+                let text = match screen.menu.selected_item() {
+                    RecruitSubMenuItem::Weapon => match &recruit.weapon {
+                        Some(weapon) => {
+                            format!("{}\n\n{}", weapon.name, weapon_stats(&weapon.stats))
+                        }
+                        None => "No weapon".to_string(),
+                    },
+                    RecruitSubMenuItem::Armor => match &recruit.armor {
+                        Some(armor) => format!("{}\n\n{}", armor.name, recruit_stats(&armor.stats)),
+                        None => "No armor".to_string(),
+                    },
+                    _ => format!(
+                        "{}\n\n{}\n\n{}",
+                        recruit.rank,
+                        recruit.metadata.backstory,
+                        recruit_stats(&recruit.stats)
+                    ),
+                };
+
+                draw_panel(text);
+            }
+            None => {
+                self.menu.draw();
+
+                if let BarracksMenuItem::Recruit(recruit) = self.menu.selected_item() {
+                    let recruit = &recruit.data;
+                    draw_panel(format!(
+                        "{}\n{}\n\nWeapon: {}\nArmor: {}",
+                        recruit.metadata.name,
+                        recruit.rank,
+                        recruit.weapon.as_ref().map_or("-", |w| w.name.as_str()),
+                        recruit.armor.as_ref().map_or("-", |a| a.name.as_str()),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Draw the details of the selected item on the right side of the screen.
+fn draw_panel(text: String) {
+    DrawCommand::text(text)
+        .position(screen_width() / 2.0, 80.0)
+        .font_size(20)
+        .color(WHITE)
+        .z_index(ZIndex::MenuText)
+        .schedule();
+}
+
+/// Format the stats which matter for a recruit and their armor.
+fn recruit_stats(stats: &Stats) -> String {
+    format!(
+        "Mobility: {}\nAim: {}\nHealth: {}\nArmor: {}\nDodge: {}\nDefense: {}",
+        stats.mobility(),
+        stats.aim(),
+        stats.health(),
+        stats.armor(),
+        stats.dodge(),
+        stats.defense(),
+    )
+}
+
+/// Format the stats which matter for a weapon.
+fn weapon_stats(stats: &Stats) -> String {
+    format!(
+        "Damage: {}\nSpread: {}\nCrit Chance: {}\nRange: {}\nAmmo: {}\nArea: {}",
+        stats.damage(),
+        stats.spread(),
+        stats.crit_chance(),
+        stats.range(),
+        stats.ammo(),
+        stats.area_size(),
+    )
+}
+
+impl Menu<BarracksMenuItem> {
+    pub fn barracks(recruits: &[WithRef<Recruit>]) -> Self {
+        Self {
+            title: Some("Barracks".to_string()),
+            items: vec![BarracksMenuItem::Hire]
+                .into_iter()
+                .chain(
+                    recruits
+                        .iter()
+                        .map(|r| BarracksMenuItem::Recruit(Box::new(r.clone()))),
+                )
+                .chain(vec![BarracksMenuItem::Back])
+                .collect(),
+            selected_item: 0,
+            window: Some(20),
+        }
+    }
+}
+
+impl Display for BarracksMenuItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BarracksMenuItem::Hire => write!(f, "Hire Recruit"),
+            BarracksMenuItem::Recruit(recruit) => {
+                write!(f, "{} ({})", recruit.data.metadata.name, recruit.data.rank)
+            }
+            BarracksMenuItem::Back => write!(f, "Back"),
+        }
+    }
+}
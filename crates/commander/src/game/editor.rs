@@ -15,10 +15,12 @@ use crate::{
     },
     game::Selectable,
     input::InputCommand,
-    types::{Cursor, Direction, GameMap, ID, Map, Preset, TileType},
+    types::{Cursor, Direction, DistanceField, GameMap, ID, Map, Preset, TileType},
 };
 
 const EDITOR_GRID_KEY: &str = "editor_state";
+/// Name given to the presets published from the editor.
+const PUBLISHED_MAP_NAME: &str = "Custom Map";
 
 #[derive(Clone)]
 pub struct Editor {
@@ -53,6 +55,7 @@ struct EditorMenu {
 #[derive(Clone)]
 enum EditorMenuItem {
     UploadPreset,
+    Publish,
     Reset,
     Exit,
 }
@@ -68,6 +71,8 @@ enum Tool {
 
 pub enum EditorMessage {
     Play(Preset),
+    /// Publish the preset on chain.
+    Publish(Preset),
     Exit,
     None,
 }
@@ -87,6 +92,21 @@ impl Editor {
         }
     }
 
+    /// Spawn positions which can't be reached from the first one, units placed
+    /// there would never meet.
+    fn unreachable_spawns(&self) -> Vec<(u8, u8)> {
+        let Some(first) = self.spawns.first() else {
+            return vec![];
+        };
+
+        let field = DistanceField::new(&self.grid, *first, None);
+        self.spawns
+            .iter()
+            .filter(|spawn| field.cost(**spawn).is_none())
+            .copied()
+            .collect()
+    }
+
     pub fn handle_key_press(&mut self, key: InputCommand) -> EditorMessage {
         match &mut self.mode {
             Mode::Editor => match key {
@@ -170,6 +190,23 @@ impl Editor {
                         );
                         return EditorMessage::Play(Preset::from(self.clone().into()));
                     }
+                    EditorMenuItem::Publish => {
+                        if self.spawns.is_empty() {
+                            println!("Add spawn positions before publishing");
+                            return EditorMessage::None;
+                        }
+
+                        let unreachable = self.unreachable_spawns();
+                        if !unreachable.is_empty() {
+                            println!("Spawn positions can't be reached: {:?}", unreachable);
+                            return EditorMessage::None;
+                        }
+
+                        self.mode = Mode::Editor;
+                        let mut preset: Preset = self.clone().into();
+                        preset.name = PUBLISHED_MAP_NAME.to_string();
+                        return EditorMessage::Publish(preset);
+                    }
                     EditorMenuItem::Reset => {
                         let (width, height) = self.grid.dimensions();
                         self.grid = GameMap::new(width, height);
@@ -282,6 +319,7 @@ impl EditorMenu {
         Self {
             items: vec![
                 EditorMenuItem::UploadPreset,
+                EditorMenuItem::Publish,
                 EditorMenuItem::Reset,
                 EditorMenuItem::Exit,
             ],
@@ -435,6 +473,7 @@ impl Display for EditorMenuItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             EditorMenuItem::UploadPreset => write!(f, "Play Test Preset"),
+            EditorMenuItem::Publish => write!(f, "Publish"),
             EditorMenuItem::Reset => write!(f, "Reset"),
             EditorMenuItem::Exit => write!(f, "Save and Exit"),
         }
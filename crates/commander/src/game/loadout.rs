@@ -0,0 +1,299 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+use std::fmt::Display;
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::menu::*;
+use crate::{
+    WithRef,
+    draw::{Draw, DrawCommand, ZIndex, draw},
+    game::AppComponent,
+    input::InputCommand,
+    sound::Effect,
+    types::{Armor, ID, MAX_WEAPON_UPGRADES, Recruit, Slot, Stats, UpgradeKind, Weapon},
+};
+
+/// Edits the equipment in a single `Slot` of a recruit: equip items from the
+/// inventory, take them from other recruits and manage weapon upgrades.
+#[derive(Debug, Clone)]
+pub struct LoadoutScreen {
+    pub recruit: WithRef<Recruit>,
+    pub slot: Slot,
+    pub menu: Menu<LoadoutMenuItem>,
+    /// Tier of the upgrades to attach, changed with Left and Right.
+    pub tier: u8,
+}
+
+#[derive(Debug, Clone)]
+pub enum LoadoutMenuItem {
+    Unequip,
+    EquipWeapon(Box<WithRef<Weapon>>),
+    EquipArmor(Box<WithRef<Armor>>),
+    /// Swap the items in the slot with another recruit.
+    Swap(Box<WithRef<Recruit>>, Slot),
+    AddUpgrade(UpgradeKind),
+    /// Index and the name of the upgrade.
+    RemoveUpgrade(u8, String),
+    Back,
+}
+
+/// Change to the loadout of a recruit, executed as a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LoadoutAction {
+    /// Equip an owned item to the slot.
+    Equip(Slot, ID),
+    /// Remove the item from the slot.
+    Unequip(Slot),
+    /// Swap the items in the slot with the recruit with the given ID.
+    Swap(Slot, ID),
+    /// Attach a new upgrade of the given tier to the weapon.
+    AddUpgrade(UpgradeKind, u8),
+    /// Detach the upgrade at the given index from the weapon.
+    RemoveUpgrade(u8),
+}
+
+#[derive(Debug, Clone)]
+pub enum LoadoutMessage {
+    Exit,
+    Action(LoadoutAction),
+    None,
+}
+
+impl LoadoutScreen {
+    pub fn new(
+        recruit: WithRef<Recruit>,
+        slot: Slot,
+        recruits: &[WithRef<Recruit>],
+        weapons: &[WithRef<Weapon>],
+        armor: &[WithRef<Armor>],
+    ) -> Self {
+        Self {
+            menu: Menu::loadout(&recruit, slot, recruits, weapons, armor),
+            recruit,
+            slot,
+            tier: 1,
+        }
+    }
+
+    /// The recruit as it would be after applying the selected item.
+    pub fn preview(&self) -> Recruit {
+        let mut recruit = self.recruit.data.clone();
+
+        match self.menu.selected_item() {
+            LoadoutMenuItem::Unequip => match self.slot {
+                Slot::Weapon => recruit.weapon = None,
+                Slot::Armor => recruit.armor = None,
+            },
+            LoadoutMenuItem::EquipWeapon(weapon) => recruit.weapon = Some(weapon.data.clone()),
+            LoadoutMenuItem::EquipArmor(armor) => recruit.armor = Some(armor.data.clone()),
+            LoadoutMenuItem::Swap(other, Slot::Weapon) => {
+                recruit.weapon = other.data.weapon.clone()
+            }
+            LoadoutMenuItem::Swap(other, Slot::Armor) => recruit.armor = other.data.armor.clone(),
+            LoadoutMenuItem::AddUpgrade(kind) => {
+                if let (Some(weapon), Some(upgrade)) =
+                    (&mut recruit.weapon, kind.upgrade(self.tier))
+                {
+                    weapon.add_upgrade(upgrade);
+                }
+            }
+            LoadoutMenuItem::RemoveUpgrade(index, _) => {
+                if let Some(weapon) = &mut recruit.weapon {
+                    weapon.remove_upgrade(*index as usize);
+                }
+            }
+            LoadoutMenuItem::Back => {}
+        }
+
+        recruit
+    }
+}
+
+impl AppComponent for LoadoutScreen {
+    type Message = LoadoutMessage;
+
+    fn handle_key_press(&mut self, key: InputCommand) -> Self::Message {
+        match key {
+            InputCommand::Up => self.menu.previous_item(),
+            InputCommand::Down => self.menu.next_item(),
+            InputCommand::Left => self.tier = (self.tier - 1).max(1),
+            InputCommand::Right => self.tier = (self.tier + 1).min(3),
+            InputCommand::Menu => return LoadoutMessage::Exit,
+            InputCommand::Select => {
+                let action = match self.menu.selected_item() {
+                    LoadoutMenuItem::Unequip => LoadoutAction::Unequip(self.slot),
+                    LoadoutMenuItem::EquipWeapon(weapon) => {
+                        LoadoutAction::Equip(Slot::Weapon, weapon.data.id)
+                    }
+                    LoadoutMenuItem::EquipArmor(armor) => {
+                        LoadoutAction::Equip(Slot::Armor, armor.data.id)
+                    }
+                    LoadoutMenuItem::Swap(other, slot) => LoadoutAction::Swap(*slot, other.data.id),
+                    LoadoutMenuItem::AddUpgrade(kind) => {
+                        LoadoutAction::AddUpgrade(*kind, self.tier)
+                    }
+                    LoadoutMenuItem::RemoveUpgrade(index, _) => {
+                        LoadoutAction::RemoveUpgrade(*index)
+                    }
+                    LoadoutMenuItem::Back => return LoadoutMessage::Exit,
+                };
+
+                Effect::Data.play();
+                return LoadoutMessage::Action(action);
+            }
+            _ => {}
+        }
+
+        LoadoutMessage::None
+    }
+
+    fn tick(&mut self) {
+        draw::draw_main_menu_background();
+        self.menu.draw();
+
+        let current = self.recruit.data.unit_stats();
+        let preview = self.preview();
+        let item = self.slot.item_name(&preview).unwrap_or("-");
+        let upgrades = preview
+            .weapon
+            .as_ref()
+            .map(|w| {
+                w.upgrades
+                    .iter()
+                    .map(|u| u.name.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut text = format!("{}: {}\n", self.slot, item);
+        if self.slot == Slot::Weapon {
+            text += &format!(
+                "Upgrades: {}/{}\n{}\nUpgrade tier: {} (<- ->)\n",
+                upgrades.len(),
+                MAX_WEAPON_UPGRADES,
+                upgrades.join(", "),
+                self.tier,
+            );
+        }
+
+        text += &format!("\n{}", stats_diff(&current, &preview.unit_stats()));
+
+        DrawCommand::text(text)
+            .position(screen_width() / 2.0, 80.0)
+            .font_size(20)
+            .color(WHITE)
+            .z_index(ZIndex::MenuText)
+            .schedule();
+    }
+}
+
+/// Name of a stat and its getter.
+type StatRow = (&'static str, fn(&Stats) -> i8);
+
+/// Stats shown in the loadout preview.
+const PREVIEW_STATS: [StatRow; 10] = [
+    ("Mobility", Stats::mobility),
+    ("Aim", Stats::aim),
+    ("Health", Stats::health),
+    ("Armor", Stats::armor),
+    ("Dodge", Stats::dodge),
+    ("Damage", Stats::damage),
+    ("Spread", Stats::spread),
+    ("Crit Chance", Stats::crit_chance),
+    ("Range", Stats::range),
+    ("Ammo", Stats::ammo),
+];
+
+/// Format the combined stats of a unit, marking the values which change.
+fn stats_diff(current: &Stats, preview: &Stats) -> String {
+    PREVIEW_STATS
+        .iter()
+        .map(|(name, stat)| match (stat(current), stat(preview)) {
+            (a, b) if a == b => format!("{}: {}", name, a),
+            (a, b) => format!("{}: {} -> {}", name, a, b),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Menu<LoadoutMenuItem> {
+    pub fn loadout(
+        recruit: &WithRef<Recruit>,
+        slot: Slot,
+        recruits: &[WithRef<Recruit>],
+        weapons: &[WithRef<Weapon>],
+        armor: &[WithRef<Armor>],
+    ) -> Self {
+        let mut items = Vec::new();
+        let equipped = slot.is_equipped(&recruit.data);
+
+        if equipped {
+            items.push(LoadoutMenuItem::Unequip);
+        }
+
+        match slot {
+            Slot::Weapon => {
+                if let Some(weapon) = &recruit.data.weapon {
+                    items.extend(weapon.upgrades.iter().enumerate().map(|(i, upgrade)| {
+                        LoadoutMenuItem::RemoveUpgrade(i as u8, upgrade.name.clone())
+                    }));
+
+                    if weapon.upgrades.len() < MAX_WEAPON_UPGRADES {
+                        items.extend(UpgradeKind::ALL.map(LoadoutMenuItem::AddUpgrade));
+                    }
+                }
+
+                items.extend(
+                    weapons
+                        .iter()
+                        .map(|w| LoadoutMenuItem::EquipWeapon(Box::new(w.clone()))),
+                );
+            }
+            Slot::Armor => items.extend(
+                armor
+                    .iter()
+                    .map(|a| LoadoutMenuItem::EquipArmor(Box::new(a.clone()))),
+            ),
+        }
+
+        // Swapping only makes sense if at least one of the slots is taken.
+        items.extend(
+            recruits
+                .iter()
+                .filter(|r| r.data.id != recruit.data.id)
+                .filter(|r| equipped || slot.is_equipped(&r.data))
+                .map(|r| LoadoutMenuItem::Swap(Box::new(r.clone()), slot)),
+        );
+
+        items.push(LoadoutMenuItem::Back);
+
+        Self {
+            title: Some(format!("{}: {}", recruit.data.metadata.name, slot)),
+            items,
+            selected_item: 0,
+            window: Some(20),
+        }
+    }
+}
+
+impl Display for LoadoutMenuItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadoutMenuItem::Unequip => write!(f, "Unequip"),
+            LoadoutMenuItem::EquipWeapon(weapon) => write!(f, "Equip {}", weapon.data.name),
+            LoadoutMenuItem::EquipArmor(armor) => write!(f, "Equip {}", armor.data.name),
+            LoadoutMenuItem::Swap(other, slot) => write!(
+                f,
+                "Swap with {} ({})",
+                other.data.metadata.name,
+                slot.item_name(&other.data).unwrap_or("-")
+            ),
+            LoadoutMenuItem::AddUpgrade(kind) => write!(f, "Add {}", kind),
+            LoadoutMenuItem::RemoveUpgrade(_, name) => write!(f, "Remove {}", name),
+            LoadoutMenuItem::Back => write!(f, "Back"),
+        }
+    }
+}
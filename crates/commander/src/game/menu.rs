@@ -1,7 +1,7 @@
 // Copyright (c) Sui Potatoes
 // SPDX-License-Identifier: MIT
 
-use std::fmt::Display;
+use std::{fmt::Display, path::PathBuf};
 
 use macroquad::prelude::*;
 use quad_storage::STORAGE;
@@ -10,13 +10,15 @@ use sui_sdk_types::Address;
 use crate::{
     WithRef,
     config::{MENU_FONT_COLOR as TEXT_COLOR, MENU_FONT_SIZE as FONT_SIZE},
-    draw::{self, Draw, DrawCommand, ZIndex},
+    draw::{self, Align, Draw, DrawCommand, ZIndex},
     sound::Effect,
-    types::{Preset, Recruit, Replay},
+    types::{Host, ID, Map, Preset, Recruit, Replay, TileType},
 };
 
 const SELECTED_COLOR: Color = WHITE;
 const TITLE_FONT_SIZE: f32 = 40.0;
+/// Size of the map preview relative to the screen width.
+const PREVIEW_SIZE: f32 = 0.4;
 
 #[derive(Debug, Clone)]
 /// Draws the menu based on the items, tracks the currently selected item. Reacts
@@ -34,6 +36,8 @@ pub struct Menu<Item> {
 #[derive(Debug, Clone, Copy)]
 pub enum MainMenuItem {
     StartGame,
+    Multiplayer,
+    Barracks,
     Login,
     Address(Address),
     Replays,
@@ -45,6 +49,34 @@ pub enum MainMenuItem {
 #[derive(Debug, Clone)]
 pub enum ReplayMenuItem {
     Replay(WithRef<Replay>),
+    /// Show the exported replay files.
+    OpenFile,
+    /// Replay file in the storage dir.
+    File(PathBuf),
+    Back,
+}
+
+#[derive(Debug, Clone)]
+pub enum PresetMenuItem {
+    Preset(Box<WithRef<Preset>>),
+    Back,
+}
+
+#[derive(Debug, Clone)]
+pub enum SquadMenuItem {
+    /// Start the game with the selected recruits.
+    Start,
+    /// Owned recruit and the index of the spawn it is assigned to, if any.
+    Recruit(Box<WithRef<Recruit>>, Option<usize>),
+    Back,
+}
+
+#[derive(Debug, Clone)]
+pub enum LobbyMenuItem {
+    /// Host a new game from the preset.
+    Host(Box<WithRef<Preset>>),
+    /// Join a game hosted by another player.
+    Join(WithRef<Host>),
     Back,
 }
 
@@ -53,6 +85,7 @@ pub enum RecruitSubMenuItem {
     Stats,
     Weapon,
     Armor,
+    Dismiss,
     Back,
 }
 
@@ -71,6 +104,8 @@ impl Menu<MainMenuItem> {
         let items = if let Some(_) = address {
             vec![
                 MainMenuItem::StartGame,
+                MainMenuItem::Multiplayer,
+                MainMenuItem::Barracks,
                 MainMenuItem::Replays,
                 MainMenuItem::Editor,
                 MainMenuItem::Settings,
@@ -97,7 +132,7 @@ impl Menu<ReplayMenuItem> {
     pub fn replays(replays: &Vec<WithRef<Replay>>) -> Self {
         Self {
             title: Some("Replays".to_string()),
-            items: vec![ReplayMenuItem::Back]
+            items: vec![ReplayMenuItem::Back, ReplayMenuItem::OpenFile]
                 .into_iter()
                 .chain(replays.iter().map(|r| ReplayMenuItem::Replay(r.clone())))
                 .collect(),
@@ -105,6 +140,212 @@ impl Menu<ReplayMenuItem> {
             window: Some(20),
         }
     }
+
+    pub fn replay_files(files: Vec<PathBuf>) -> Self {
+        Self {
+            title: Some("Replay Files".to_string()),
+            items: vec![ReplayMenuItem::Back]
+                .into_iter()
+                .chain(files.into_iter().map(ReplayMenuItem::File))
+                .collect(),
+            selected_item: 0,
+            window: Some(20),
+        }
+    }
+}
+
+impl Menu<PresetMenuItem> {
+    pub fn presets(presets: &[WithRef<Preset>]) -> Self {
+        Self {
+            title: Some("Select Map".to_string()),
+            items: vec![PresetMenuItem::Back]
+                .into_iter()
+                .chain(
+                    presets
+                        .iter()
+                        .map(|p| PresetMenuItem::Preset(Box::new(p.clone()))),
+                )
+                .collect(),
+            selected_item: 0,
+            window: Some(20),
+        }
+    }
+
+    /// Draw a thumbnail of the selected preset's map and its details on the
+    /// right side of the screen.
+    pub fn draw_preview(&self) {
+        let preset = match self.selected_item() {
+            PresetMenuItem::Preset(preset) => &preset.data,
+            PresetMenuItem::Back => return,
+        };
+
+        let spawns = preset.positions.iter().rev().cloned().collect::<Vec<_>>();
+        let map = &preset.map;
+        let (x0, y0, size) = draw_map_preview(map, &spawns, &[]);
+
+        DrawCommand::text(format!(
+            "Author: {}\nPlayed: {}\nSize: {}x{}\nSpawns: {}",
+            ID::from(preset.author),
+            preset.popularity,
+            map.rows(),
+            map.cols(),
+            preset.positions.len()
+        ))
+        .position(x0, y0 + size + FONT_SIZE * 2.0)
+        .font_size(FONT_SIZE as u16)
+        .color(WHITE)
+        .z_index(ZIndex::MenuText)
+        .schedule();
+    }
+}
+
+/// Draw a thumbnail of the `map` on the right side of the screen, with the
+/// `spawns` numbered in placement order and the ones with an `assigned`
+/// recruit highlighted. Returns the position and the size of the thumbnail.
+pub fn draw_map_preview(map: &Map, spawns: &[Vec<u8>], assigned: &[Option<ID>]) -> (f32, f32, f32) {
+    let size = screen_width() * PREVIEW_SIZE;
+    let (x0, y0) = (screen_width() - size - 20.0, TITLE_FONT_SIZE * 2.0);
+    let tile = size / map.rows().max(map.cols()) as f32;
+    let wall = (tile / 5.0).max(1.0);
+
+    DrawCommand::rectangle(x0, y0, map.cols() as f32 * tile, map.rows() as f32 * tile)
+        .color(BLACK.with_alpha(0.6))
+        .z_index(ZIndex::MenuText)
+        .ignore_padding()
+        .schedule();
+
+    for (i, row) in map.grid.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            let (x, y) = (x0 + j as f32 * tile, y0 + i as f32 * tile);
+            let walls = match cell.tile_type {
+                TileType::Empty => continue,
+                TileType::Obstacle => vec![(x, y, tile, tile)],
+                TileType::Cover {
+                    left,
+                    top,
+                    right,
+                    bottom,
+                } => [
+                    (left, (x, y, wall, tile)),
+                    (top, (x, y, tile, wall)),
+                    (right, (x + tile - wall, y, wall, tile)),
+                    (bottom, (x, y + tile - wall, tile, wall)),
+                ]
+                .into_iter()
+                .filter(|(cover, _)| *cover > 0)
+                .map(|(_, rect)| rect)
+                .collect(),
+            };
+
+            for (x, y, w, h) in walls {
+                DrawCommand::rectangle(x, y, w, h)
+                    .color(LIGHTGRAY)
+                    .z_index(ZIndex::MenuText)
+                    .ignore_padding()
+                    .schedule();
+            }
+        }
+    }
+
+    for (i, position) in spawns.iter().enumerate() {
+        let (x, y) = (
+            x0 + position[1] as f32 * tile,
+            y0 + position[0] as f32 * tile,
+        );
+        let color = match assigned.get(i) {
+            Some(Some(_)) => GREEN,
+            _ => SKYBLUE,
+        };
+        DrawCommand::rectangle(x + tile / 4.0, y + tile / 4.0, tile / 2.0, tile / 2.0)
+            .color(color)
+            .z_index(ZIndex::MenuText)
+            .ignore_padding()
+            .schedule();
+        DrawCommand::text((i + 1).to_string())
+            .position(x + tile / 2.0, y + tile / 2.0)
+            .align(Align::Center)
+            .font_size((tile / 2.0).max(8.0) as u16)
+            .color(BLACK)
+            .z_index(ZIndex::MenuText)
+            .schedule();
+    }
+
+    (x0, y0, size)
+}
+
+impl Menu<SquadMenuItem> {
+    /// Lists the owned recruits, keeping the spawns they are `assigned` to.
+    pub fn squad(recruits: &[WithRef<Recruit>], assigned: &[Option<ID>]) -> Self {
+        Self {
+            title: Some("Select Squad".to_string()),
+            items: vec![SquadMenuItem::Start]
+                .into_iter()
+                .chain(recruits.iter().map(|r| {
+                    let spawn = assigned.iter().position(|id| *id == Some(r.data.id));
+                    SquadMenuItem::Recruit(Box::new(r.clone()), spawn)
+                }))
+                .chain(vec![SquadMenuItem::Back])
+                .collect(),
+            selected_item: 0,
+            window: Some(20),
+        }
+    }
+
+    /// Recruit assigned to each of the `spawns`, `None` for the spawns which
+    /// get a new recruit.
+    pub fn assigned_recruits(&self, spawns: usize) -> Vec<Option<ID>> {
+        let mut assigned = vec![None; spawns];
+        for item in self.items.iter() {
+            if let SquadMenuItem::Recruit(recruit, Some(spawn)) = item
+                && let Some(slot) = assigned.get_mut(*spawn)
+            {
+                *slot = Some(recruit.data.id);
+            }
+        }
+
+        assigned
+    }
+
+    /// Move the selected recruit to the next free spawn out of `spawns`, or
+    /// out of the squad if there are no more free spawns.
+    pub fn assign_next_spawn(&mut self, spawns: usize) {
+        let taken = self.assigned_recruits(spawns);
+        let index = self.selected_item;
+        if let SquadMenuItem::Recruit(_, spawn) = &mut self.items[index] {
+            let from = spawn.map_or(0, |s| s + 1);
+            *spawn = (from..spawns).find(|s| taken[*s].is_none());
+        }
+    }
+}
+
+impl Menu<LobbyMenuItem> {
+    /// Lists open games hosted by other players, followed by the presets
+    /// that can be used to host a new game.
+    pub fn lobby(
+        presets: &[WithRef<Preset>],
+        hosted_games: &[WithRef<Host>],
+        address: Option<Address>,
+    ) -> Self {
+        Self {
+            title: Some("Multiplayer".to_string()),
+            items: vec![LobbyMenuItem::Back]
+                .into_iter()
+                .chain(
+                    hosted_games
+                        .iter()
+                        .filter(|h| Some(h.data.host) != address)
+                        .map(|h| LobbyMenuItem::Join(h.clone())),
+                )
+                .chain(
+                    presets
+                        .iter()
+                        .map(|p| LobbyMenuItem::Host(Box::new(p.clone()))),
+                )
+                .collect(),
+            selected_item: 0,
+            window: Some(20),
+        }
+    }
 }
 
 impl Menu<RecruitSubMenuItem> {
@@ -115,6 +356,7 @@ impl Menu<RecruitSubMenuItem> {
                 RecruitSubMenuItem::Stats,
                 RecruitSubMenuItem::Weapon,
                 RecruitSubMenuItem::Armor,
+                RecruitSubMenuItem::Dismiss,
                 RecruitSubMenuItem::Back,
             ],
             selected_item: 0,
@@ -200,21 +442,25 @@ impl<T: Display> Draw for Menu<T> {
     }
 }
 
+/// Check if there's a game stored in the storage.
+pub fn has_active_game() -> bool {
+    STORAGE.lock().unwrap().get("active_game").is_some()
+}
+
 // === Display impls ===
 
 impl Display for MainMenuItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // check if there's a game stored in the storage
-        let has_active_game = STORAGE.lock().unwrap().get("active_game").is_some();
-
         match self {
             MainMenuItem::StartGame => {
-                if has_active_game {
+                if has_active_game() {
                     write!(f, "Resume Game")
                 } else {
                     write!(f, "Start Game")
                 }
             }
+            MainMenuItem::Multiplayer => write!(f, "Multiplayer"),
+            MainMenuItem::Barracks => write!(f, "Barracks"),
             MainMenuItem::Address(_address) => write!(f, "Logged in"),
             MainMenuItem::Login => write!(f, "Login (Google)"),
             MainMenuItem::Replays => write!(f, "Replays"),
@@ -236,17 +482,69 @@ impl Display for ReplayMenuItem {
                     replay.data.history.0.len()
                 )
             }
+            ReplayMenuItem::OpenFile => write!(f, "Open File"),
+            ReplayMenuItem::File(path) => write!(
+                f,
+                "{}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
             ReplayMenuItem::Back => write!(f, "Back"),
         }
     }
 }
 
+impl Display for PresetMenuItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PresetMenuItem::Preset(preset) => write!(f, "{}", preset.data.name),
+            PresetMenuItem::Back => write!(f, "Back"),
+        }
+    }
+}
+
+impl Display for SquadMenuItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SquadMenuItem::Start => write!(f, "Start Game"),
+            SquadMenuItem::Recruit(recruit, spawn) => {
+                let recruit = &recruit.data;
+                write!(
+                    f,
+                    "[{}] {} ({}) W: {} A: {}",
+                    spawn.map_or(" ".to_string(), |s| (s + 1).to_string()),
+                    recruit.metadata.name,
+                    recruit.rank,
+                    recruit.weapon.as_ref().map_or("-", |w| w.name.as_str()),
+                    recruit.armor.as_ref().map_or("-", |a| a.name.as_str()),
+                )
+            }
+            SquadMenuItem::Back => write!(f, "Back"),
+        }
+    }
+}
+
+impl Display for LobbyMenuItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LobbyMenuItem::Host(preset) => write!(f, "Host: {}", preset.data.name),
+            LobbyMenuItem::Join(host) => write!(
+                f,
+                "Join: {} (by {})",
+                host.data.name,
+                ID::from(host.data.host)
+            ),
+            LobbyMenuItem::Back => write!(f, "Back"),
+        }
+    }
+}
+
 impl Display for RecruitSubMenuItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             RecruitSubMenuItem::Stats => write!(f, "Stats"),
             RecruitSubMenuItem::Weapon => write!(f, "Weapon"),
             RecruitSubMenuItem::Armor => write!(f, "Armor"),
+            RecruitSubMenuItem::Dismiss => write!(f, "Dismiss"),
             RecruitSubMenuItem::Back => write!(f, "Back"),
         }
     }
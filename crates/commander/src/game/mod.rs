@@ -4,18 +4,24 @@
 // SPDX-License-Identifier: MIT
 
 mod app;
+mod barracks;
 mod editor;
+mod loadout;
 mod menu;
 mod object;
 mod play;
 mod player;
+mod report;
 mod settings;
 
 pub use app::*;
+pub use barracks::*;
 pub use editor::*;
+pub use loadout::*;
 pub use menu::*;
 pub use object::*;
 pub use player::*;
+pub use report::*;
 pub use settings::*;
 
 pub use play::PlayMessage;
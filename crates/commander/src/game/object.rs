@@ -147,6 +147,14 @@ impl GameObject {
         self.statuses.insert(key.to_string(), animation);
     }
 
+    /// Check if the object has a timed animation or status still running.
+    /// Static and looping animations don't count.
+    pub fn is_animating(&self) -> bool {
+        self.animation.duration.is_some()
+            || self.animation.chain.is_some()
+            || self.statuses.values().any(|s| s.duration.is_some())
+    }
+
     /// Remove status animation with a given key.
     pub fn remove_status_animation(&mut self, key: &str) {
         let _ = self.statuses.remove(&key.to_string());
@@ -342,6 +350,21 @@ impl Animation {
         }
     }
 
+    /// Play the timed animations of the chain `multiplier` times faster.
+    /// Animations without a duration keep running at their own pace.
+    pub fn speed(mut self, multiplier: f64) -> Self {
+        if let Some(duration) = self.duration.as_mut() {
+            *duration /= multiplier;
+
+            if let AnimationType::MoveSprite { fps, .. } = &mut self.type_ {
+                *fps /= multiplier;
+            }
+        }
+
+        self.chain = self.chain.map(|chain| Box::new(chain.speed(multiplier)));
+        self
+    }
+
     pub fn chain(&mut self, animation: Animation) {
         let mut nested_anim_ref = &mut self.chain;
 
@@ -7,15 +7,18 @@ use macroquad::prelude::*;
 use sui_sdk_types::Address;
 
 use crate::{
+    config::TILE_WIDTH,
     draw::{
         self, Align, Asset, Draw, DrawCommand, Highlight, Sprite, SpriteSheet, Texture, ZIndex,
         grid_to_world,
     },
     game::{Animation, AnimationType, AppComponent, GameObject, ProcessedRecord, Selectable},
     input::InputCommand,
+    rules::{self, RulesError},
     sound::{self, Effect},
     types::{
-        self, Direction, Game, GameMap, GridPath, History, ID, Param, Preset, Record, Target, Unit,
+        self, Direction, DistanceField, Game, GameMap, GridPath, History, ID, Map, Param, Preset,
+        Record, Recruit, Stats, Target, TileType, Unit, Visibility,
     },
 };
 
@@ -27,10 +30,34 @@ pub struct Play {
     players: Vec<Address>,
     cursor: PlayCursor,
     highlight: Option<Highlight>,
+    /// Tiles the selected unit can reach with a dash, shown in Walk mode.
+    dash_highlight: Option<Highlight>,
     objects: HashMap<ID, GameObject>,
     selected_unit: Option<Rc<RefCell<Unit>>>,
+    /// Paths of the selected unit, recomputed when the map changes.
+    distance_field: Option<DistanceField>,
+    /// Tiles seen by the current player's units, `None` if there's no fog of
+    /// war and the whole map is visible.
+    visibility: Option<Visibility>,
     pub test_preset: Option<Preset>,
     pub secondary_index: usize,
+    /// Authoritative copy of the game used by the rules engine in test mode.
+    simulation: Option<Box<Simulation>>,
+    /// Current player and unit leaders, used to enforce turn ownership.
+    ownership: Option<Box<Ownership>>,
+}
+
+/// Local game state for play-testing presets, mirrors the state on chain.
+struct Simulation {
+    map: GameMap,
+    rng: rules::Rng,
+}
+
+/// Tells apart units of the current player from the opponent's.
+struct Ownership {
+    address: Address,
+    /// Leaders of the units on the map, by recruit ID.
+    leaders: HashMap<ID, Address>,
 }
 
 pub enum Mode {
@@ -56,19 +83,36 @@ pub enum PlayMessage {
     None,
     /// Perform a Move action.
     Move(GridPath),
+    /// Move twice in a row, spending 2 AP, in a single transaction.
+    Dash(GridPath, GridPath),
     Attack((u8, u8), (u8, u8)),
     Reload((u8, u8)),
+    /// Throw a grenade from the first position at the second one.
+    Grenade((u8, u8), (u8, u8)),
     BackToEditor,
     NextTurn,
     QuitGame,
     Exit,
 }
 
+impl PlayMessage {
+    /// Paths of a `Move` or a `Dash`, each one is a separate `move_unit` call
+    /// in Move. Empty for other messages.
+    pub fn move_legs(&self) -> Vec<GridPath> {
+        match self {
+            PlayMessage::Move(path) => vec![path.clone()],
+            PlayMessage::Dash(first, second) => vec![first.clone(), second.clone()],
+            _ => vec![],
+        }
+    }
+}
+
 #[derive(Debug)]
 enum ActionMode {
     Walk,
     Shoot,
     Reload,
+    Grenade,
 }
 
 struct PlayCursor {
@@ -90,6 +134,11 @@ impl AppComponent for Play {
                     return match menu.selected_item() {
                         PlayMenuItem::NextTurn => {
                             self.mode = Mode::Play;
+                            if !self.is_my_turn() {
+                                println!("Not your turn");
+                                return PlayMessage::None;
+                            }
+
                             self.game.next_turn(self.test_preset.is_some());
                             PlayMessage::NextTurn
                         }
@@ -121,10 +170,24 @@ impl AppComponent for Play {
                         self.cursor.set_to(unit_pos);
                     }
                     ActionMode::Walk => self.cursor.move_to(i.clone().into()),
+                    // move the cursor within the throwing range of the unit
+                    ActionMode::Grenade if self.selected_unit.is_some() => {
+                        let unit = *self.selected_unit.clone().unwrap().borrow();
+                        let unit_pos = self.game.unit_position(&unit).unwrap();
+                        let prev_pos = self.cursor.position;
+
+                        self.cursor.move_to(i.clone().into());
+
+                        if types::manhattan_distance(unit_pos, self.cursor.position)
+                            > types::GRENADE_RANGE
+                        {
+                            self.cursor.set_to(prev_pos);
+                        }
+                    }
                     ActionMode::Shoot if self.selected_unit.is_some() => {
                         let unit = *self.selected_unit.clone().unwrap().borrow(); // TODO: handle unwrap
                         let unit_pos = self.game.unit_position(&unit).unwrap();
-                        let targets = self.game.targets(unit_pos);
+                        let targets = self.visible_targets(unit_pos);
 
                         self.secondary_index += 1;
 
@@ -159,15 +222,20 @@ impl AppComponent for Play {
                     let pos = self.cursor.position;
 
                     if self.selected_unit.is_none() {
+                        if !self.is_my_turn() {
+                            return PlayMessage::None;
+                        }
+
+                        let unit = self.unit_at(pos);
+                        if unit.is_some_and(|u| !self.is_own_unit(u.borrow().recruit)) {
+                            println!("Not your unit");
+                            return PlayMessage::None;
+                        }
+
                         self.select_unit(pos);
                         sound::random_effect(&[Effect::VoiceYes, Effect::VoiceCommander]);
                         self.action_mode = ActionMode::Walk;
-
-                        if let Some(unit) = &self.selected_unit {
-                            let distance = unit.borrow().stats;
-                            let tiles = self.game.walkable_tiles(pos, distance.mobility() as u8);
-                            self.highlight = Some(Highlight(tiles, BLUE.with_alpha(0.2)));
-                        }
+                        self.update_distance_field();
                     } else {
                         match self.action_mode {
                             ActionMode::Reload => {
@@ -188,34 +256,58 @@ impl AppComponent for Play {
                                 return PlayMessage::Reload(unit_pos);
                             }
                             ActionMode::Walk => {
-                                let unit = *self.selected_unit.clone().unwrap().borrow();
-                                let unit_pos = self.game.unit_position(&unit).unwrap();
-
-                                if let Some(path) =
-                                    self.game.trace_path(unit_pos, self.cursor.position)
-                                {
-                                    if path.len() > 1 {
+                                let target = self.cursor.position;
+                                let path = self
+                                    .distance_field
+                                    .as_ref()
+                                    .and_then(|field| field.path(target))
+                                    .filter(|path| path.len() > 1);
+
+                                match (path, self.move_cost(target)) {
+                                    (Some(path), Some(ap)) => {
                                         let path = GridPath::new(path);
-                                        self.move_selected_unit(path.clone());
+                                        let mobility = mobility(
+                                            &self.selected_unit.as_ref().unwrap().borrow(),
+                                        );
+
+                                        if !self.move_selected_unit(path.clone(), ap) {
+                                            return PlayMessage::None;
+                                        }
+
                                         self.deselect_unit();
                                         sound::random_effect(&[
                                             Effect::VoiceWillDo,
                                             Effect::VoiceMovingToTheTarget,
                                         ]);
-                                        return PlayMessage::Move(path);
+
+                                        return match ap {
+                                            1 => PlayMessage::Move(path),
+                                            _ => {
+                                                let (first, second) =
+                                                    path.split_at(mobility as usize);
+                                                PlayMessage::Dash(first, second)
+                                            }
+                                        };
                                     }
+                                    (Some(_), None) => println!("Target is out of range"),
+                                    (None, _) => {}
                                 }
                             }
                             ActionMode::Shoot => {
                                 let unit = *self.selected_unit.clone().unwrap().borrow();
                                 let unit_pos = self.game.unit_position(&unit).unwrap();
-                                let targets = self.game.targets(unit_pos);
+                                let targets = self.visible_targets(unit_pos);
 
                                 self.remove_target_animations();
 
                                 if let Some(target) =
                                     targets.iter().find(|t| t.position == self.cursor.position)
                                 {
+                                    if target.chance.is_none() {
+                                        println!("Target is out of range");
+                                        return PlayMessage::None;
+                                    }
+
                                     if unit.ap.value() == 0 || unit.ammo.value() == 0 {
                                         println!(
                                             "No AP ({}) or ammo ({}) left",
@@ -234,6 +326,33 @@ impl AppComponent for Play {
                                     return PlayMessage::Attack(unit_pos, target.position);
                                 }
                             }
+                            ActionMode::Grenade => {
+                                let unit = *self.selected_unit.clone().unwrap().borrow();
+                                let unit_pos = self.game.unit_position(&unit).unwrap();
+                                let target = self.cursor.position;
+
+                                if unit.ap.value() == 0 || unit.grenade_used {
+                                    println!("No AP ({}) or grenade already used", unit.ap.value());
+                                    return PlayMessage::None;
+                                }
+
+                                if types::manhattan_distance(unit_pos, target)
+                                    > types::GRENADE_RANGE
+                                {
+                                    println!("Target is out of range");
+                                    return PlayMessage::None;
+                                }
+
+                                let mut unit = self.selected_unit.as_ref().unwrap().borrow_mut();
+                                unit.ap.decrease(1);
+                                unit.grenade_used = true;
+
+                                self.highlight = None;
+                                self.action_mode = ActionMode::Walk;
+
+                                sound::Effect::VoiceAttack.play();
+                                return PlayMessage::Grenade(unit_pos, target);
+                            }
                         }
                     }
                 }
@@ -255,22 +374,44 @@ impl AppComponent for Play {
                             self.cursor.set_to(unit_pos);
                         }
                     }
+                    ActionMode::Grenade => {
+                        self.remove_target_animations();
+                        self.highlight = None;
+
+                        if let Some(unit) = &self.selected_unit.clone() {
+                            let unit = unit.borrow();
+                            let unit_pos = self.game.unit_position(&unit).unwrap();
+
+                            if unit.grenade_used {
+                                println!("Grenade already used");
+                                self.handle_key_press(InputCommand::Tool);
+                                return PlayMessage::None;
+                            }
+
+                            let (rows, cols) = (self.game.rows(), self.game.cols());
+                            let tiles = (0..rows)
+                                .flat_map(|x| (0..cols).map(move |y| (x, y)))
+                                .filter(|p| {
+                                    types::manhattan_distance(unit_pos, *p) <= types::GRENADE_RANGE
+                                })
+                                .collect::<Vec<_>>();
+
+                            self.cursor.set_to(unit_pos);
+                            self.highlight = Some(Highlight(tiles, ORANGE.with_alpha(0.1)));
+                        }
+                    }
                     ActionMode::Walk => {
                         self.remove_target_animations();
                         self.highlight = None;
                         if let Some(unit) = &self.selected_unit {
-                            let distance = unit.borrow().stats;
                             let unit_pos = self.game.unit_position(&unit.borrow()).unwrap();
-                            let tiles = self
-                                .game
-                                .walkable_tiles(unit_pos, distance.mobility() as u8);
 
                             self.game.targets(unit_pos).iter().for_each(|t| {
                                 self.objects
                                     .get_mut(&t.target_id)
                                     .map(|o| o.remove_status_animation("hit_chance"));
                             });
-                            self.highlight = Some(Highlight(tiles, BLUE.with_alpha(0.2)));
+                            self.update_distance_field();
                         }
                     }
                     ActionMode::Shoot => {
@@ -278,21 +419,22 @@ impl AppComponent for Play {
                         self.highlight = None;
                         if let Some(unit) = &self.selected_unit {
                             let unit_pos = self.game.unit_position(&unit.borrow()).unwrap();
-                            let targets = self.game.targets(unit_pos);
+                            let targets = self.visible_targets(unit_pos);
 
                             targets.iter().for_each(|t| {
-                                if let Some(chance) = t.chance {
-                                    if let Some(object) = self.objects.get_mut(&t.target_id) {
-                                        object.add_status_animation(
-                                            "hit_chance",
-                                            Animation::status(
-                                                format!("Chance: {}%", chance),
-                                                24,
-                                                RED,
-                                                None,
-                                            ),
-                                        );
+                                let text = match (t.chance, t.dodge) {
+                                    (Some(chance), 0) => format!("Chance: {}%", chance),
+                                    (Some(chance), dodge) => {
+                                        format!("Chance: {}% Dodge: {}%", chance, dodge)
                                     }
+                                    (None, _) => "Out of range".to_string(),
+                                };
+
+                                if let Some(object) = self.objects.get_mut(&t.target_id) {
+                                    object.add_status_animation(
+                                        "hit_chance",
+                                        Animation::status(text, 24, RED, None),
+                                    );
                                 }
                             });
 
@@ -313,6 +455,18 @@ impl AppComponent for Play {
         let units = self.units();
         let turn = self.game.turn;
 
+        // Enemy units out of sight are not drawn.
+        let hidden = units
+            .iter()
+            .map(|unit| unit.borrow())
+            .filter(|unit| {
+                self.game
+                    .unit_position(unit)
+                    .is_some_and(|position| !self.is_visible(position))
+            })
+            .map(|unit| unit.recruit)
+            .collect::<Vec<_>>();
+
         for (id, object) in self.objects.iter_mut() {
             if let Some(unit) = units.iter().find(|u| u.borrow().recruit == *id) {
                 let mut unit = unit.borrow_mut();
@@ -327,6 +481,10 @@ impl AppComponent for Play {
                 object.add_status_animation("ammo", Animation::ammo(unit.ammo, BLACK, None));
             }
 
+            if hidden.contains(id) {
+                continue;
+            }
+
             object.tick(get_time());
         }
 
@@ -347,11 +505,35 @@ impl Play {
             .take_records_with_effects()
             .iter()
             .for_each(|record| match record {
+                ProcessedRecord::Move(coords, _path) => {
+                    // Own moves are applied before the transaction is sent,
+                    // so the unit is already at the destination.
+                    if coords.len() < 2 || self.unit_at(coords[0]).is_none() {
+                        return;
+                    }
+
+                    self.charge_opponent(coords[0], |unit| unit.ap.decrease(1));
+                    self.move_unit(&GridPath::new(coords.clone()));
+                }
+                ProcessedRecord::Reload(position) => self.charge_opponent(*position, |unit| {
+                    unit.ap.decrease(1);
+                    unit.ammo.reset();
+                }),
+                ProcessedRecord::NextTurn(turn) => {
+                    if *turn > self.game.turn {
+                        self.game.turn = *turn;
+                    }
+                }
                 ProcessedRecord::Attack {
-                    origin: _,
+                    origin,
                     target,
                     effects,
                 } => {
+                    self.charge_opponent(*origin, |unit| {
+                        unit.ap.deplete();
+                        unit.ammo.decrease(1);
+                    });
+
                     let target_unit = match self.unit_at(*target) {
                         Some(unit) => unit,
                         None => return,
@@ -407,73 +589,137 @@ impl Play {
                                     ),
                                 );
                         }
-                        Record::UnitKIA(id) => {
-                            self.objects
-                                .get_mut(&target_unit.borrow().recruit)
-                                .unwrap()
-                                .animation = Animation::none();
+                        Record::UnitKIA(id) => self.remove_unit(*id),
+                        _ => println!("Unsupported attack effect: {:?}", effect),
+                    })
+                }
+                ProcessedRecord::Grenade {
+                    radius,
+                    target,
+                    effects,
+                } => {
+                    let mut effects = effects.iter().peekable();
+                    let area = self.game.grenade_area(*target, *radius as u8);
 
-                            let unit = self
-                                .units()
-                                .iter()
-                                .find(|u| u.borrow().recruit == *id)
-                                .unwrap()
-                                .clone();
+                    for (x, y) in area.iter().cloned() {
+                        let tile = &mut self.game.grid[x as usize][y as usize];
 
-                            let position = self.game.unit_position(&unit.borrow()).unwrap();
+                        if matches!(tile.tile_type, TileType::Cover { .. }) {
+                            tile.tile_type = TileType::Empty;
+                        }
 
-                            // Remove the unit from the grid.
-                            self.game.grid[position.0 as usize][position.1 as usize]
-                                .unit
-                                .take()
-                                .unwrap();
+                        // The chain emits a `Damage` record for every unit in
+                        // the area, followed by `UnitKIA` if the unit died.
+                        let unit = match tile.unit.clone() {
+                            Some(unit) => unit,
+                            None => continue,
+                        };
+
+                        if let Some(Record::Damage(damage)) = effects.next() {
+                            unit.borrow_mut().hp.decrease(*damage as u16);
+                            self.objects
+                                .get_mut(&unit.borrow().recruit)
+                                .unwrap()
+                                .set_color(RED, 1.0)
+                                .add_status_animation(
+                                    "damage",
+                                    Animation::status(
+                                        format!("Damage! {}", damage),
+                                        24,
+                                        RED,
+                                        Some(2.0),
+                                    ),
+                                );
                         }
-                        _ => println!("Unsupported attack effect: {:?}", effect),
-                    })
+
+                        if let Some(Record::UnitKIA(id)) = effects.peek() {
+                            self.remove_unit(*id);
+                            effects.next();
+                        }
+                    }
                 }
                 _ => println!("Unsupported effect: {:?}", record),
             });
-    }
 
-    /// Converts a `PlayMessage` into a `History` with random effects.
-    pub fn message_to_random_history(&self, message: PlayMessage) -> History {
-        if self.test_preset.is_none() {
-            panic!("Cannot convert message to random history in non-test mode!");
+        // Units moved or died, paths of the selected unit may have changed.
+        if self.distance_field.is_some() {
+            self.update_distance_field();
         }
 
-        match message {
-            PlayMessage::Attack((x0, y0), (x1, y1)) => {
-                let range = types::chebyshev_distance((x0, y0), (x1, y1));
-                let is_hit = rand::gen_range(0, 10) > range;
-                let damage = rand::gen_range(0, 5);
-                let is_kia = self
-                    .unit_at((x1, y1))
-                    .is_some_and(|unit| unit.borrow().hp.value() <= damage);
-
-                let mut history = vec![
-                    Record::Attack {
-                        origin: vec![x0 as u16, y0 as u16],
-                        target: vec![x1 as u16, y1 as u16],
-                    },
-                    match (is_hit, damage) {
-                        (true, damage) if damage > 0 => Record::Damage(damage as u8),
-                        (true, 0) => Record::Dodged,
-                        (false, _) => Record::Miss,
-                        _ => unreachable!("Invalid damage or hit chance"),
-                    },
-                ];
+        self.update_visibility();
+    }
 
-                if is_kia {
-                    history.push(Record::UnitKIA(
-                        self.selected_unit.as_ref().unwrap().borrow().recruit,
-                    ));
-                }
+    /// Create a `Play` for testing a `Preset` from the `Editor`. Actions are
+    /// not sent to the network but run through the local rules engine.
+    pub fn new_test(preset: Preset) -> Self {
+        let mut play = Play::from(Game::from(preset.clone()));
+        let map: Map = play.game.clone().into();
 
-                History(history)
-            }
-            PlayMessage::Move(path) => History(vec![Record::Move(path.to_direction_path())]),
-            PlayMessage::Reload((x, y)) => History(vec![Record::Reload(vec![x as u16, y as u16])]),
-            _ => History(vec![]),
+        play.test_preset = Some(preset);
+        play.simulation = Some(Box::new(Simulation {
+            map: map.into(),
+            rng: rules::Rng::new(::rand::random()),
+        }));
+        play
+    }
+
+    /// Run the `PlayMessage` through the rules engine in test mode. Returns
+    /// the `History` the chain would emit for this action.
+    pub fn simulate(&mut self, message: PlayMessage) -> Result<History, RulesError> {
+        let Simulation { map, rng } = match self.simulation.as_deref_mut() {
+            Some(simulation) => simulation,
+            None => panic!("Cannot simulate actions in non-test mode!"),
+        };
+
+        let records = match message {
+            PlayMessage::Move(path) => vec![rules::move_unit(map, &path.to_direction_path())?],
+            PlayMessage::Dash(first, second) => vec![
+                rules::move_unit(map, &first.to_direction_path())?,
+                rules::move_unit(map, &second.to_direction_path())?,
+            ],
+            PlayMessage::Attack(p0, p1) => rules::perform_attack(map, rng, p0, p1)?,
+            PlayMessage::Reload(p) => vec![rules::perform_reload(map, p)?],
+            PlayMessage::Grenade(p0, p1) => rules::perform_grenade(map, rng, p0, p1)?,
+            PlayMessage::NextTurn => vec![rules::next_turn(map)],
+            _ => vec![],
+        };
+
+        Ok(History(records))
+    }
+
+    /// Set the current player and the leaders of the units in the game, so
+    /// that only own units can be controlled in a multiplayer game.
+    pub fn set_owners(&mut self, address: Option<Address>, recruits: &[Recruit]) {
+        self.ownership = address.map(|address| {
+            Box::new(Ownership {
+                address,
+                leaders: recruits.iter().map(|r| (r.id, r.leader)).collect(),
+            })
+        });
+
+        self.update_visibility();
+    }
+
+    /// Players take turns in the order they joined the game. Single player
+    /// games and play tests are always on the current player's turn.
+    pub fn is_my_turn(&self) -> bool {
+        if self.test_preset.is_some() || self.players.len() < 2 {
+            return true;
+        }
+
+        let player = self.players[self.game.turn as usize % self.players.len()];
+        self.ownership.as_ref().is_some_and(|o| o.address == player)
+    }
+
+    /// Whether the unit can be controlled by the current player. Units with
+    /// unknown leaders are only controllable outside of multiplayer games.
+    fn is_own_unit(&self, id: ID) -> bool {
+        match self.ownership.as_ref() {
+            Some(o) => match o.leaders.get(&id) {
+                Some(leader) => *leader == o.address,
+                None => self.players.len() < 2,
+            },
+            None => self.players.len() < 2,
         }
     }
 
@@ -493,15 +739,121 @@ impl Play {
         units
     }
 
+    /// Remove a KIA unit from the grid and stop its animations.
+    fn remove_unit(&mut self, id: ID) {
+        if let Some(object) = self.objects.get_mut(&id) {
+            object.animation = Animation::none();
+        }
+
+        let unit = match self.units().into_iter().find(|u| u.borrow().recruit == id) {
+            Some(unit) => unit,
+            None => return println!("Unit {} not found", id),
+        };
+
+        let position = self.game.unit_position(&unit.borrow()).unwrap();
+        self.game.grid[position.0 as usize][position.1 as usize]
+            .unit
+            .take();
+    }
+
     fn deselect_unit(&mut self) {
         if let Some(unit) = &self.selected_unit {
             let object = self.objects.get_mut(&unit.borrow().recruit).unwrap();
             object.add_status_animation("shadow", Animation::none());
             self.selected_unit = None;
+            self.distance_field = None;
             self.highlight = None;
+            self.dash_highlight = None;
+        }
+    }
+
+    /// Recompute the `DistanceField` of the selected unit and highlight the
+    /// tiles it can reach for 1 AP and with a dash in the Walk mode. The field
+    /// is not limited, so paths to the tiles out of range can be previewed.
+    ///
+    /// Units hidden by the fog of war are not known to block the way, if they
+    /// do, the chain rejects the move.
+    fn update_distance_field(&mut self) {
+        let visibility = self.visibility.as_ref();
+        let step_cost = |map: &GameMap, from, to| {
+            let allowed = match visibility {
+                Some(visibility) if !visibility.is_visible(to) => types::can_pass(map, from, to),
+                _ => types::can_step(map, from, to),
+            };
+
+            allowed.then_some(1)
+        };
+
+        self.distance_field = self.selected_unit.as_ref().and_then(|unit| {
+            let position = self.game.unit_position(&unit.borrow())?;
+            Some(DistanceField::with_costs(
+                &self.game, position, None, step_cost,
+            ))
+        });
+
+        if let (ActionMode::Walk, Some(field)) = (&self.action_mode, &self.distance_field) {
+            let (mut moves, mut dashes) = (Vec::new(), Vec::new());
+            for tile in field.reachable() {
+                match self.move_cost(tile) {
+                    Some(1) => moves.push(tile),
+                    Some(_) => dashes.push(tile),
+                    None => {}
+                }
+            }
+
+            self.highlight = Some(Highlight(moves, BLUE.with_alpha(0.2)));
+            self.dash_highlight = Some(Highlight(dashes, YELLOW.with_alpha(0.2)));
+        }
+    }
+
+    /// Recompute the tiles seen by the current player's units. There's no fog
+    /// of war in play tests and single player games, nor for spectators.
+    fn update_visibility(&mut self) {
+        if self.test_preset.is_some() || self.players.len() < 2 || self.ownership.is_none() {
+            self.visibility = None;
+            return;
+        }
+
+        let positions = self
+            .units()
+            .iter()
+            .map(|unit| unit.borrow())
+            .filter(|unit| self.is_own_unit(unit.recruit))
+            .filter_map(|unit| self.game.unit_position(&unit))
+            .collect::<Vec<_>>();
+
+        match self.visibility.as_mut() {
+            Some(visibility) => visibility.update(&self.game, &positions),
+            None => self.visibility = Some(Visibility::new(&self.game, &positions)),
         }
     }
 
+    /// Whether the current player can see the tile.
+    fn is_visible(&self, position: (u8, u8)) -> bool {
+        self.visibility
+            .as_ref()
+            .is_none_or(|visibility| visibility.is_visible(position))
+    }
+
+    /// Targets of the unit at `origin` the current player can see.
+    fn visible_targets(&self, origin: (u8, u8)) -> Vec<Target> {
+        self.game
+            .targets(origin)
+            .into_iter()
+            .filter(|target| self.is_visible(target.position))
+            .collect()
+    }
+
+    /// AP cost of moving the selected unit to the `target`: 1 within the
+    /// unit's mobility and 2 for a dash. `None` if the unit doesn't have
+    /// enough AP left or the target can't be reached.
+    fn move_cost(&self, target: (u8, u8)) -> Option<u16> {
+        let unit = self.selected_unit.as_ref()?.borrow();
+        let distance = self.distance_field.as_ref()?.cost(target)?;
+
+        types::move_ap_cost(distance, mobility(&unit)).filter(|ap| *ap <= unit.ap.value())
+    }
+
     fn remove_target_animations(&mut self) {
         self.objects
             .iter_mut()
@@ -535,55 +887,87 @@ impl Play {
         })
     }
 
-    fn move_selected_unit(&mut self, grid_path: GridPath) {
+    /// Move the selected unit along the path, spending `ap` AP. Returns
+    /// whether the move was applied.
+    fn move_selected_unit(&mut self, grid_path: GridPath, ap: u16) -> bool {
         if grid_path.is_empty() {
-            return;
+            return false;
         }
 
-        if let Some(unit) = &mut self.selected_unit {
+        if let Some(unit) = self.selected_unit.clone() {
             assert!(
                 self.game
                     .unit_position(&unit.borrow())
                     .is_some_and(|pos| pos == grid_path.0[0])
             );
 
-            if unit.borrow().ap.value() == 0 {
-                return println!("No AP left");
+            if unit.borrow().ap.value() < ap {
+                println!("Not enough AP left");
+                return false;
             }
 
-            unit.borrow_mut().ap.decrease(1);
-
-            // Move the unit on the Map.
-            let first = *grid_path.0.first().unwrap();
-            let last = *grid_path.0.last().unwrap();
-            let unit_rc = self.game.grid[first.0 as usize][first.1 as usize]
-                .unit
-                .take()
-                .unwrap();
-            self.game.grid[last.0 as usize][last.1 as usize]
-                .unit
-                .replace(unit_rc);
-
-            // Animate unit's movement.
-            let obj = self.objects.get_mut(&unit.borrow().recruit).unwrap();
-            let mut animations = grid_path
-                .to_path_segments(self.game.dimensions())
-                .into_iter()
-                .map(|segment| segment.into())
-                .collect::<Vec<_>>();
-
-            let start_animation: Animation = animations.remove(0);
-            let mut animation =
-                animations
-                    .into_iter()
-                    .fold(start_animation, |mut acc, animation| {
-                        acc.chain(animation);
-                        acc
-                    });
+            // A unit hidden by the fog is in the way, the chain would reject
+            // the move.
+            if grid_path.0[1..]
+                .iter()
+                .any(|pos| self.unit_at(*pos).is_some())
+            {
+                println!("Path is blocked");
+                return false;
+            }
 
-            animation.chain(static_unit_animation());
+            unit.borrow_mut().ap.decrease(ap);
+            self.move_unit(&grid_path);
+            return true;
+        }
+
+        false
+    }
+
+    /// Move the unit at the start of the path to its end and animate it.
+    fn move_unit(&mut self, grid_path: &GridPath) {
+        // Move the unit on the Map.
+        let first = *grid_path.0.first().unwrap();
+        let last = *grid_path.0.last().unwrap();
+        let unit_rc = self.game.grid[first.0 as usize][first.1 as usize]
+            .unit
+            .take()
+            .unwrap();
+        let recruit = unit_rc.borrow().recruit;
+        self.game.grid[last.0 as usize][last.1 as usize]
+            .unit
+            .replace(unit_rc);
+
+        // Animate unit's movement.
+        let obj = self.objects.get_mut(&recruit).unwrap();
+        let mut animations = grid_path
+            .to_path_segments(self.game.dimensions())
+            .into_iter()
+            .map(|segment| segment.into())
+            .collect::<Vec<_>>();
+
+        let start_animation: Animation = animations.remove(0);
+        let mut animation = animations
+            .into_iter()
+            .fold(start_animation, |mut acc, animation| {
+                acc.chain(animation);
+                acc
+            });
+
+        animation.chain(static_unit_animation());
 
-            obj.animation = animation;
+        obj.animation = animation;
+        self.update_visibility();
+    }
+
+    /// Apply the cost of an action to the unit at `position` if it belongs to
+    /// the opponent. Own actions are paid for when they are selected.
+    fn charge_opponent(&mut self, position: (u8, u8), cost: impl FnOnce(&mut Unit)) {
+        if let Some(unit) = self.unit_at(position) {
+            let recruit = unit.borrow().recruit;
+            if !self.is_own_unit(recruit) {
+                cost(&mut unit.borrow_mut());
+            }
         }
     }
 }
@@ -626,12 +1010,17 @@ impl From<Game> for Play {
             action_mode: ActionMode::Walk,
             cursor: PlayCursor::new((0, 0), game.dimensions()),
             highlight: None,
+            dash_highlight: None,
             objects,
             players,
             game,
             selected_unit: None,
+            distance_field: None,
+            visibility: None,
             test_preset: None,
             secondary_index: 0,
+            simulation: None,
+            ownership: None,
         }
     }
 }
@@ -645,11 +1034,15 @@ impl From<Preset> for Game {
             ..
         } = value;
 
+        // Units get the default Recruit stats with the default weapon, same
+        // as freshly minted Recruits in `commander::new_game`.
+        let stats = Stats::default().add(&Stats::default_weapon());
+
         for (i, pos) in positions.iter().enumerate() {
-            map.grid[pos[0] as usize][pos[1] as usize].unit = Some(Unit {
-                recruit: ID(Address::from_hex(format!("0x{}", i)).unwrap()),
-                ..Default::default()
-            });
+            map.grid[pos[0] as usize][pos[1] as usize].unit = Some(Unit::from_stats(
+                ID(Address::from_hex(format!("0x{}", i)).unwrap()),
+                stats,
+            ));
         }
 
         Game {
@@ -707,18 +1100,29 @@ impl PlayCursor {
 impl Draw for Play {
     fn draw(&self) {
         draw::draw_texture_background(self.game.dimensions(), Texture::Background);
-        self.game.draw();
+        match &self.visibility {
+            Some(visibility) => self.game.draw_with_visibility(visibility),
+            None => self.game.draw(),
+        }
 
         match self.action_mode {
             ActionMode::Walk => self.cursor.draw_with_color(BLUE),
             ActionMode::Shoot => self.cursor.draw_with_color(RED),
             ActionMode::Reload => self.cursor.draw_with_color(GREEN),
+            ActionMode::Grenade => self.cursor.draw_with_color(ORANGE),
+        };
+
+        let turn = if self.is_my_turn() {
+            "Your turn"
+        } else {
+            "Opponent's turn"
         };
 
         draw::DrawCommand::text(format!(
-            "Mode: {}\nUnit: {}",
+            "Mode: {}\nUnit: {}\n{}",
             self.action_mode,
-            self.selected_unit.is_some()
+            self.selected_unit.is_some(),
+            turn
         ))
         .position(screen_width() / 2.0, screen_height() - 40.0 - 20.0)
         .background(BLACK.with_alpha(0.5))
@@ -736,14 +1140,47 @@ impl Draw for Play {
                 draw::draw_highlight(highlight, self.game.dimensions());
             }
 
-            if matches!(self.action_mode, ActionMode::Walk) {
-                if let Some(unit_position) = self.game.unit_position(&unit.borrow()) {
-                    if let Some(path_to_cursor) =
-                        self.game.trace_path(unit_position, self.cursor.position)
-                    {
-                        draw::draw_path(&path_to_cursor, self.game.dimensions());
-                    }
-                }
+            if let (ActionMode::Walk, Some(highlight)) = (&self.action_mode, &self.dash_highlight) {
+                draw::draw_highlight(highlight, self.game.dimensions());
+            }
+
+            // Preview the path to the cursor and its cost before moving.
+            if matches!(self.action_mode, ActionMode::Walk)
+                && let Some(path_to_cursor) = self
+                    .distance_field
+                    .as_ref()
+                    .and_then(|field| field.path(self.cursor.position))
+                    .filter(|path| path.len() > 1)
+            {
+                let dimensions = self.game.dimensions();
+                let (color, label) = match self.move_cost(self.cursor.position) {
+                    Some(1) => (BLUE, "1 AP".to_string()),
+                    Some(ap) => (YELLOW, format!("{} AP (Dash)", ap)),
+                    None => (RED, "Out of range".to_string()),
+                };
+
+                draw::draw_path(&path_to_cursor, dimensions, color);
+
+                let (scale_x, _) = draw::get_scale(dimensions);
+                let position = grid_to_world(self.cursor.position, dimensions);
+                DrawCommand::text(label)
+                    .position(position.x + TILE_WIDTH * scale_x / 2.0, position.y - 4.0)
+                    .align(Align::Center)
+                    .font_size(16)
+                    .color(color)
+                    .background(BLACK.with_alpha(0.5))
+                    .z_index(ZIndex::UnitStatus)
+                    .schedule();
+            }
+
+            if matches!(self.action_mode, ActionMode::Grenade) {
+                let area = self
+                    .game
+                    .grenade_area(self.cursor.position, types::GRENADE_RADIUS);
+                draw::draw_highlight(
+                    &Highlight(area, ORANGE.with_alpha(0.4)),
+                    self.game.dimensions(),
+                );
             }
         }
     }
@@ -827,7 +1264,8 @@ impl ActionMode {
         match self {
             ActionMode::Walk => *self = ActionMode::Shoot,
             ActionMode::Shoot => *self = ActionMode::Reload,
-            ActionMode::Reload => *self = ActionMode::Walk,
+            ActionMode::Reload => *self = ActionMode::Grenade,
+            ActionMode::Grenade => *self = ActionMode::Walk,
         };
         self
     }
@@ -845,6 +1283,7 @@ impl Display for ActionMode {
             ActionMode::Walk => write!(f, "Walk"),
             ActionMode::Shoot => write!(f, "Shoot"),
             ActionMode::Reload => write!(f, "Reload"),
+            ActionMode::Grenade => write!(f, "Grenade"),
         }
     }
 }
@@ -871,3 +1310,8 @@ fn static_unit_animation() -> Animation {
         ..Default::default()
     }
 }
+
+/// Number of tiles the unit can walk with one AP.
+fn mobility(unit: &Unit) -> u16 {
+    unit.stats.mobility().max(0) as u16
+}
@@ -3,7 +3,7 @@
 
 use std::{
     cell::RefCell,
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     convert::TryFrom,
     fmt::Display,
     rc::Rc,
@@ -12,7 +12,7 @@ use std::{
 use macroquad::prelude::*;
 use sui_sdk_types::Address;
 
-use super::{Animation, AnimationType, GameObject};
+use super::{Animation, AnimationType, GameObject, Report};
 use crate::{
     config::{MENU_FONT_SIZE as FONT_SIZE, TILE_HEIGHT, TILE_WIDTH},
     draw::{
@@ -21,7 +21,10 @@ use crate::{
     },
     game::AppComponent,
     input::InputCommand,
-    types::{Cursor, Direction, GameMap, GridPath, History, ID, Map, Preset, Record, Replay, Unit},
+    types::{
+        Cursor, Direction, GameMap, GridPath, History, ID, Map, Param, Preset, Record, Replay,
+        Roster, TileType, Unit,
+    },
 };
 
 /// A Player for `Replay`s. Allows playing the replay step by step.
@@ -34,14 +37,56 @@ pub struct Player {
     pub records: VecDeque<ProcessedRecord>,
     /// Stores processed actions.
     pub processed_records: VecDeque<ProcessedRecord>,
-    /// Stores units that have been KIA to restore them on prev_action.
-    pub kia_units: Vec<Rc<RefCell<Unit>>>,
+    /// Snapshots of the board taken at the start of the replay and of every
+    /// turn, keyed by the number of processed actions. Used to seek backwards.
+    pub snapshots: BTreeMap<usize, Snapshot>,
     /// Highlight the tiles that are affected by the action.
     pub highlight: Option<Highlight>,
     /// Stores units that are currently on the Map and their animations.
     pub objects: HashMap<ID, GameObject>,
-    /// Just a value that allows distinguishing between units.
+    /// Number of recruits placed so far, the index of the next placement.
     pub id_counter: u8,
+    /// Recruits who took part in the game.
+    pub roster: Roster,
+    /// IDs of the placed recruits in the order of placement, if known.
+    pub placements: Vec<Option<ID>>,
+    /// Automatic playback of the replay.
+    pub playback: Playback,
+    /// Action log panel.
+    pub timeline: Timeline,
+}
+
+/// Number of entries shown in the timeline panel at once.
+const TIMELINE_WINDOW: usize = 24;
+
+/// Share of the screen width taken by the timeline panel.
+const TIMELINE_WIDTH: f32 = 0.3;
+
+/// Action log of the replay. When focused, entries can be selected to seek the
+/// `Player` to the state right after the selected action.
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    pub focused: bool,
+    /// Index of the selected action.
+    pub selected: usize,
+}
+
+/// Playback speed multipliers, the default is 1x.
+const PLAYBACK_SPEEDS: [f64; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// Pause between actions during playback at 1x, in seconds.
+const PLAYBACK_DELAY: f64 = 0.8;
+
+/// Controls the automatic playback of the `Player`. Actions are advanced once
+/// all animations of the previous one are finished and the delay, scaled by
+/// the speed, has passed.
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub playing: bool,
+    /// Index in the `PLAYBACK_SPEEDS`.
+    pub speed: usize,
+    /// Time when the animations of the last action have finished.
+    pub idle_since: Option<f64>,
 }
 
 const COLOR_PLACE: Color = Color {
@@ -72,6 +117,20 @@ const COLOR_RELOAD: Color = Color {
     a: 0.2,
 };
 
+const COLOR_GRENADE: Color = Color {
+    r: 200.0,
+    g: 100.0,
+    b: 0.0,
+    a: 0.3,
+};
+
+/// State of the `Player` at some point of the replay. The map is a deep copy
+/// and is never modified, restoring creates another copy.
+pub struct Snapshot {
+    pub map: GameMap,
+    pub id_counter: u8,
+}
+
 #[derive(Debug)]
 pub enum ProcessedRecord {
     Reload((u8, u8)),
@@ -94,30 +153,106 @@ pub enum ProcessedRecord {
 pub enum PlayerMessage {
     None,
     Exit,
+    /// Show the report of the replayed game.
+    Report(Report),
 }
 
 impl AppComponent for Player {
     type Message = PlayerMessage;
 
     fn handle_key_press(&mut self, key: InputCommand) -> PlayerMessage {
-        match key {
-            InputCommand::Menu => return PlayerMessage::Exit,
-            InputCommand::Right => self
-                .next_action()
-                .unwrap_or_else(|e| eprintln!("Error: {}", e)),
-            InputCommand::Left => self
-                .prev_action()
-                .unwrap_or_else(|e| eprintln!("Error: {}", e)),
-            _ => {}
+        if self.timeline.focused {
+            let total = self.processed_records.len() + self.records.len();
+            let result = match key {
+                InputCommand::Menu | InputCommand::Back => {
+                    self.timeline.focused = false;
+                    Ok(())
+                }
+                InputCommand::Up => {
+                    self.timeline.selected = self.timeline.selected.saturating_sub(1);
+                    Ok(())
+                }
+                InputCommand::Down => {
+                    self.timeline.selected = (self.timeline.selected + 1).min(total - 1);
+                    Ok(())
+                }
+                InputCommand::Select => {
+                    self.playback.pause();
+                    self.seek(self.timeline.selected + 1)
+                }
+                _ => Ok(()),
+            };
+
+            result.unwrap_or_else(|e| eprintln!("Error: {}", e));
+            return PlayerMessage::None;
         }
+
+        let result = match key {
+            InputCommand::Menu => return PlayerMessage::Exit,
+            InputCommand::Back
+                if !self.records.is_empty() || !self.processed_records.is_empty() =>
+            {
+                self.timeline.focused = true;
+                self.timeline.selected = self.processed_records.len().saturating_sub(1);
+                Ok(())
+            }
+            InputCommand::Select | InputCommand::Right if self.records.is_empty() => {
+                return PlayerMessage::Report(self.report());
+            }
+            InputCommand::Select => {
+                self.playback.toggle();
+                Ok(())
+            }
+            InputCommand::Up => {
+                self.playback.speed_up();
+                Ok(())
+            }
+            InputCommand::Down => {
+                self.playback.slow_down();
+                Ok(())
+            }
+            InputCommand::Right => {
+                self.playback.pause();
+                self.next_action()
+            }
+            InputCommand::Left => {
+                self.playback.pause();
+                self.prev_action()
+            }
+            InputCommand::Action => self.jump_to_turn(self.turn() + 1),
+            InputCommand::Tool => self.jump_to_turn(self.turn().saturating_sub(1)),
+            _ => Ok(()),
+        };
+
+        result.unwrap_or_else(|e| eprintln!("Error: {}", e));
         PlayerMessage::None
     }
 
     fn tick(&mut self) {
-        for (_id, object) in self.objects.iter_mut() {
+        let units = self
+            .map
+            .as_ref()
+            .map(|map| {
+                map.grid
+                    .iter()
+                    .flatten()
+                    .filter_map(|t| t.unit.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for (id, object) in self.objects.iter_mut() {
+            if let Some(unit) = units.iter().find(|u| u.borrow().recruit == *id) {
+                object.add_status_animation("hp", Animation::hp(unit.borrow().hp, RED, None));
+            }
+
             object.tick(get_time());
         }
 
+        if self.playback.playing {
+            self.tick_playback();
+        }
+
         self.draw();
     }
 }
@@ -129,27 +264,147 @@ impl Player {
             records: replay.history.take_records_with_effects().into(),
             preset_id: replay.preset_id,
             processed_records: VecDeque::new(),
-            kia_units: Vec::new(),
+            snapshots: BTreeMap::new(),
             highlight: None,
             objects: HashMap::new(),
             id_counter: 0,
+            roster: Roster::default(),
+            placements: Vec::new(),
+            playback: Playback::default(),
+            timeline: Timeline::default(),
         }
     }
 
     pub fn stop_all_animations(&mut self) {
         for (_id, object) in self.objects.iter_mut() {
+            // KIA units stay hidden.
+            if matches!(object.animation.type_, AnimationType::Hidden) {
+                continue;
+            }
+
             object.skip_all_animations();
             object.animation = static_unit_animation();
         }
     }
 
+    /// Set the map of the replay, only the first call has an effect.
     pub fn add_preset(&mut self, preset: Preset) {
-        self.map = Some(preset.map.clone().into());
+        if self.map.is_some() {
+            return;
+        }
+
+        let map: GameMap = preset.map.clone().into();
+
+        self.snapshots.clear();
+        self.snapshots.insert(
+            0,
+            Snapshot {
+                map: map.snapshot(),
+                id_counter: 0,
+            },
+        );
+        self.map = Some(map);
+        self.resolve_placements();
+    }
+
+    /// Set the recruits who took part in the game and replay the actions
+    /// processed so far with their actual stats.
+    pub fn set_roster(&mut self, roster: Roster) -> Result<(), anyhow::Error> {
+        self.roster = roster;
+        self.resolve_placements();
+
+        if self.map.is_none() {
+            return Ok(());
+        }
+
+        // Snapshots taken so far contain units without the roster applied.
+        let index = self.processed_records.len();
+        self.snapshots.retain(|index, _| *index == 0);
+        self.restore_snapshot(0)?;
+        self.seek(index)
+    }
+
+    /// Match the recruits in the roster with the `RecruitPlaced` actions.
+    fn resolve_placements(&mut self) {
+        self.placements = self.report().units.iter().map(|u| u.id).collect();
+    }
+
+    /// Report of the whole replayed game.
+    pub fn report(&self) -> Report {
+        let records = self.processed_records.iter().chain(self.records.iter());
+        let mut report = Report::new(records);
+        report.identify(&self.roster);
+        report
+    }
+
+    /// Current turn of the replayed game.
+    pub fn turn(&self) -> u16 {
+        self.map.as_ref().map_or(0, |map| map.turn)
+    }
+
+    /// Advance the playback if the previous action is over. Stops at the end
+    /// of the replay.
+    fn tick_playback(&mut self) {
+        if self.objects.values().any(|o| o.is_animating()) {
+            self.playback.idle_since = None;
+            return;
+        }
+
+        let now = get_time();
+        let idle_since = *self.playback.idle_since.get_or_insert(now);
+
+        if now - idle_since < PLAYBACK_DELAY / self.playback.multiplier() {
+            return;
+        }
+
+        self.playback.idle_since = None;
+        if self.next_action().is_err() {
+            self.playback.pause();
+        }
+    }
+
+    /// Play or rewind the replay to the start of the given turn, right after
+    /// its `NextTurn` action. Turn 0 rewinds to the very start.
+    pub fn jump_to_turn(&mut self, turn: u16) -> Result<(), anyhow::Error> {
+        let index = match turn {
+            0 => 0,
+            turn => {
+                self.processed_records
+                    .iter()
+                    .chain(self.records.iter())
+                    .position(|r| matches!(r, ProcessedRecord::NextTurn(t) if *t == turn))
+                    .ok_or(anyhow::anyhow!("Turn {} is not in the replay", turn))?
+                    + 1
+            }
+        };
+
+        self.seek(index)
+    }
+
+    /// Play or rewind the replay until `index` actions are processed. Going
+    /// back restores the closest snapshot before the `index` and plays the
+    /// rest of the actions from there.
+    pub fn seek(&mut self, index: usize) -> Result<(), anyhow::Error> {
+        if index < self.processed_records.len() {
+            self.restore_snapshot(index)?;
+        }
+
+        while self.processed_records.len() < index {
+            self.next_action()?;
+        }
+
+        self.stop_all_animations();
+        self.playback.idle_since = None;
+
+        Ok(())
     }
 
     pub fn next_action(&mut self) -> Result<(), anyhow::Error> {
         self.stop_all_animations();
 
+        // Animations follow the playback speed, not only the pauses.
+        let speed = self.playback.multiplier();
+
         let action: ProcessedRecord = self
             .records
             .pop_front()
@@ -160,22 +415,26 @@ impl Player {
             ProcessedRecord::RecruitPlaced(x, y) => {
                 self.highlight = Some(Highlight(vec![(*x, *y)], COLOR_PLACE));
 
+                // Units which can't be identified get a placeholder ID.
+                let index = self.id_counter as usize;
+                let unit = match self.placements.get(index).copied().flatten() {
+                    Some(id) => match self.roster.recruit(&id) {
+                        Some(recruit) => Unit::from_stats(id, recruit.unit_stats()),
+                        None => Unit {
+                            recruit: id,
+                            ..Default::default()
+                        },
+                    },
+                    None => Unit {
+                        recruit: ID(Address::from_bytes([self.id_counter; 32]).unwrap()),
+                        ..Default::default()
+                    },
+                };
+
                 let tile = &mut self.map.as_mut().unwrap().grid[*x as usize][*y as usize];
-                let mut unit = Unit::default();
-                unit.recruit = ID(Address::from_bytes([self.id_counter; 32]).unwrap());
                 tile.unit = Some(Rc::new(RefCell::new(unit)));
 
-                let position = grid_to_world((*x, *y), self.map.as_ref().unwrap().dimensions());
-
-                self.objects.insert(
-                    unit.recruit,
-                    GameObject::new(
-                        position,
-                        self.map.as_ref().unwrap().dimensions(),
-                        static_unit_animation(),
-                        Some(Sprite::Shadow.load().unwrap()),
-                    ),
-                );
+                self.insert_object(unit.recruit, (*x, *y));
                 self.id_counter += 1;
             }
             ProcessedRecord::NextTurn(turn) => {
@@ -212,7 +471,7 @@ impl Player {
                     }
 
                     start_animation.chain(static_unit_animation());
-                    obj.animation = start_animation;
+                    obj.animation = start_animation.speed(speed);
                 }
 
                 let end_tile = &mut self.map.as_mut().unwrap().grid[end.0 as usize][end.1 as usize];
@@ -236,9 +495,15 @@ impl Player {
                 let target_unit = tile.unit.as_ref().unwrap();
                 let target_obj = self.objects.get_mut(&target_unit.borrow().recruit).unwrap();
 
+                for effect in effects.iter() {
+                    if let Record::Damage(damage) | Record::CriticalHit(damage) = effect {
+                        target_unit.borrow_mut().hp.decrease(*damage as u16);
+                    }
+                }
+
                 effects
                     .iter()
-                    .map(|e| Animation::status(e.to_string(), 25, RED, Some(1.0)))
+                    .map(|e| Animation::status(e.to_string(), 25, RED, Some(1.0)).speed(speed))
                     .reduce(|mut acc, e| {
                         acc.chain(e);
                         acc
@@ -248,119 +513,183 @@ impl Player {
                 if let Some(Record::UnitKIA(_)) =
                     effects.iter().find(|e| matches!(e, Record::UnitKIA(_)))
                 {
-                    let unit = tile
-                        .unit
-                        .take()
-                        .ok_or(anyhow::anyhow!("Failed to get unit"))?;
-
-                    self.kia_units.push(unit);
+                    self.kill_unit(*target)?;
                 }
             }
             ProcessedRecord::Reload(pos) => {
                 self.highlight = Some(Highlight(vec![*pos], COLOR_RELOAD));
             }
-            c @ _ => {
-                println!("Skipping action: {:?}", c);
-            }
-        }
+            ProcessedRecord::Grenade {
+                radius,
+                target,
+                effects,
+            } => {
+                let map = self
+                    .map
+                    .as_mut()
+                    .ok_or(anyhow::anyhow!("Failed to get map"))?;
+                let area = map.grenade_area(*target, *radius as u8);
+                let mut effects = effects.iter().peekable();
+                let mut kia = Vec::new();
 
-        self.processed_records.push_back(action);
+                self.highlight = Some(Highlight(area.clone(), COLOR_GRENADE));
 
-        Ok(())
-    }
+                for (x, y) in area.iter().cloned() {
+                    let tile = &mut map.grid[x as usize][y as usize];
 
-    pub fn prev_action(&mut self) -> Result<(), anyhow::Error> {
-        if self.processed_records.len() == 0 {
-            return Err(anyhow::anyhow!("Action History is empty"));
-        }
+                    if matches!(tile.tile_type, TileType::Cover { .. }) {
+                        tile.tile_type = TileType::Empty;
+                    }
 
-        let action = self
-            .processed_records
-            .pop_back()
-            .ok_or(anyhow::anyhow!("Failed to get previous action"))?;
+                    // Every unit in the area gets a `Damage` record, followed
+                    // by `UnitKIA` if the unit died.
+                    let unit = match tile.unit.clone() {
+                        Some(unit) => unit,
+                        None => continue,
+                    };
+
+                    let mut statuses = Vec::new();
+                    if let Some(Record::Damage(damage)) = effects.next() {
+                        unit.borrow_mut().hp.decrease(*damage as u16);
+                        statuses.push(format!("Damage! {}", damage));
+                    }
 
-        match &action {
-            ProcessedRecord::RecruitPlaced(x, y) => {
-                self.highlight = Some(Highlight(vec![(*x, *y)], COLOR_PLACE));
+                    if let Some(Record::UnitKIA(_)) = effects.peek() {
+                        effects.next();
+                        kia.push((x, y));
+                        statuses.push(Record::UnitKIA(unit.borrow().recruit).to_string());
+                    }
 
-                let tile = &mut self
-                    .map
-                    .as_mut()
-                    .ok_or(anyhow::anyhow!("Failed to get map"))?
-                    .grid[*x as usize][*y as usize];
+                    let status = statuses
+                        .into_iter()
+                        .map(|text| Animation::status(text, 25, RED, Some(1.0)).speed(speed))
+                        .reduce(|mut acc, e| {
+                            acc.chain(e);
+                            acc
+                        });
+
+                    if let (Some(obj), Some(status)) =
+                        (self.objects.get_mut(&unit.borrow().recruit), status)
+                    {
+                        obj.set_color(RED, 1.0 / speed)
+                            .add_status_animation("status", status);
+                    }
+                }
 
-                self.objects
-                    .remove(&tile.unit.as_ref().unwrap().borrow().recruit);
-                tile.unit = None;
-            }
-            ProcessedRecord::NextTurn(turn) => {
-                self.highlight = None;
-                self.map
-                    .as_mut()
-                    .ok_or(anyhow::anyhow!("Failed to get map"))?
-                    .turn = turn - 1;
+                for position in kia {
+                    self.kill_unit(position)?;
+                }
             }
-            ProcessedRecord::Move(coords, _) => {
-                self.highlight = Some(Highlight(coords.clone(), COLOR_MOVE));
+        }
 
-                let dimensions = self.map.as_ref().unwrap().dimensions();
-                let start = coords
-                    .first()
-                    .ok_or(anyhow::anyhow!("Failed to get start"))?;
-                let end = coords.last().ok_or(anyhow::anyhow!("Failed to get end"))?;
+        self.processed_records.push_back(action);
 
-                let map_mut = self
+        // Snapshot the start of every turn to allow seeking back to it.
+        if let Some(ProcessedRecord::NextTurn(_)) = self.processed_records.back() {
+            let index = self.processed_records.len();
+            if !self.snapshots.contains_key(&index) {
+                let map = self
                     .map
-                    .as_mut()
-                    .ok_or(anyhow::anyhow!("Failed to get map"))?;
-                let end_tile = &mut map_mut.grid[end.0 as usize][end.1 as usize];
-                let unit = end_tile
-                    .unit
-                    .take()
-                    .ok_or(anyhow::anyhow!("Failed to get unit"))?;
+                    .as_ref()
+                    .ok_or(anyhow::anyhow!("Failed to get map"))?
+                    .snapshot();
+
+                self.snapshots.insert(
+                    index,
+                    Snapshot {
+                        map,
+                        id_counter: self.id_counter,
+                    },
+                );
+            }
+        }
 
-                if let Some(obj) = self.objects.get_mut(&unit.borrow().recruit) {
-                    obj.position = grid_to_world(*start, dimensions);
-                    obj.animation = static_unit_animation();
-                }
+        Ok(())
+    }
 
-                let start_tile = &mut map_mut.grid[start.0 as usize][start.1 as usize];
-                start_tile.unit = Some(unit);
-            }
-            ProcessedRecord::Attack {
-                origin: _,
-                target,
-                effects,
-            } => {
-                self.highlight = Some(Highlight(vec![*target], COLOR_ATTACK));
+    /// Step back by one action.
+    pub fn prev_action(&mut self) -> Result<(), anyhow::Error> {
+        if self.processed_records.is_empty() {
+            return Err(anyhow::anyhow!("Action History is empty"));
+        }
 
-                let tile =
-                    &mut self.map.as_mut().unwrap().grid[target.0 as usize][target.1 as usize];
+        self.seek(self.processed_records.len() - 1)
+    }
 
-                if let Some(Record::UnitKIA(_)) =
-                    effects.iter().find(|e| matches!(e, Record::UnitKIA(_)))
-                {
-                    tile.unit.replace(
-                        self.kia_units
-                            .pop()
-                            .ok_or(anyhow::anyhow!("Failed to get unit"))?,
-                    );
+    /// Restore the latest snapshot taken at or before the `index`, actions
+    /// processed after it are moved back to the queue.
+    fn restore_snapshot(&mut self, index: usize) -> Result<(), anyhow::Error> {
+        let (&snapshot_index, snapshot) = self
+            .snapshots
+            .range(..=index)
+            .next_back()
+            .ok_or(anyhow::anyhow!("No snapshot for action {}", index))?;
+
+        let map = snapshot.map.snapshot();
+        self.id_counter = snapshot.id_counter;
+
+        while self.processed_records.len() > snapshot_index {
+            let record = self.processed_records.pop_back().unwrap();
+            self.records.push_front(record);
+        }
+
+        // Units on the restored map are new instances, rebuild their objects.
+        self.objects.clear();
+        for (x, row) in map.grid.iter().enumerate() {
+            for (y, tile) in row.iter().enumerate() {
+                if let Some(unit) = &tile.unit {
+                    self.insert_object(unit.borrow().recruit, (x as u8, y as u8));
                 }
             }
-            ProcessedRecord::Reload(pos) => {
-                self.highlight = Some(Highlight(vec![*pos], COLOR_RELOAD));
-            }
-            _ => {
-                println!("Skipping action: {:?}", action);
-            }
         }
 
-        self.records.push_front(action);
+        self.map = Some(map);
+        self.highlight = None;
 
         Ok(())
     }
 }
 
+impl Player {
+    /// Remove the unit at the position from the map and hide its object.
+    fn kill_unit(&mut self, position: (u8, u8)) -> Result<(), anyhow::Error> {
+        let tile = &mut self
+            .map
+            .as_mut()
+            .ok_or(anyhow::anyhow!("Failed to get map"))?
+            .grid[position.0 as usize][position.1 as usize];
+        let unit = tile
+            .unit
+            .take()
+            .ok_or(anyhow::anyhow!("Failed to get unit"))?;
+
+        if let Some(obj) = self.objects.get_mut(&unit.borrow().recruit) {
+            obj.animation = Animation::none();
+            obj.statuses.remove("hp");
+        }
+
+        Ok(())
+    }
+
+    /// Create the `GameObject` for the unit at the grid position.
+    fn insert_object(&mut self, id: ID, position: (u8, u8)) {
+        let dimensions = match &self.map {
+            Some(map) => map.dimensions(),
+            None => return,
+        };
+
+        self.objects.insert(
+            id,
+            GameObject::new(
+                grid_to_world(position, dimensions),
+                dimensions,
+                static_unit_animation(),
+                Some(Sprite::Shadow.load().unwrap()),
+            ),
+        );
+    }
+}
+
 impl Draw for Player {
     fn draw(&self) {
         if let Some(map) = &self.map {
@@ -372,6 +701,29 @@ impl Draw for Player {
                 draw_highlight(highlight, map.dimensions());
             }
 
+            // Name and rank of the identified recruits above their units, the
+            // recruits missing from the roster are shown as unknown.
+            for unit in map.grid.iter().flatten().filter_map(|t| t.unit.as_ref()) {
+                let id = unit.borrow().recruit;
+                if let Some(object) = self.objects.get(&id) {
+                    let label = match self.roster.recruit(&id) {
+                        Some(recruit) => format!("{} ({})", recruit.metadata.name, recruit.rank),
+                        None => "Unknown".to_string(),
+                    };
+                    let (scale_x, _) = draw::get_scale(map.dimensions());
+                    DrawCommand::text(label)
+                        .position(
+                            object.position.x + TILE_WIDTH * scale_x / 2.0,
+                            object.position.y - 4.0,
+                        )
+                        .align(Align::Center)
+                        .font_size(16)
+                        .color(WHITE)
+                        .z_index(ZIndex::UnitStatus)
+                        .schedule();
+                }
+            }
+
             let bottom = screen_height();
 
             // Draw the turn number, the action number in the very bottom.
@@ -397,13 +749,142 @@ impl Draw for Player {
             .z_index(ZIndex::ModalText)
             .schedule();
 
-            DrawCommand::text("Arrow keys to control the Replay; Menu or Esc to exit".to_string())
-                .position(screen_width() / 2.0, bottom - 10.0)
-                .align(Align::Center)
+            self.draw_timeline();
+
+            let status = match self.playback.playing {
+                true => format!("Playing {}x", self.playback.multiplier()),
+                false => "Paused".to_string(),
+            };
+
+            DrawCommand::text(status)
+                .position(screen_width() - 20.0, bottom - 10.0)
+                .align(Align::Right)
                 .font_size(FONT_SIZE as u16)
                 .color(BLACK)
                 .z_index(ZIndex::ModalText)
                 .schedule();
+
+            DrawCommand::text(
+                "Left/Right to step; Select to play; Up/Down for speed; Action/Tool to skip turns; Back for timeline"
+                    .to_string(),
+            )
+            .position(screen_width() / 2.0, bottom - 10.0)
+            .align(Align::Center)
+            .font_size(FONT_SIZE as u16)
+            .color(BLACK)
+            .z_index(ZIndex::ModalText)
+            .schedule();
+        }
+    }
+}
+
+impl Player {
+    /// Draw the action log on the right side of the screen, the window follows
+    /// the selected entry when focused and the current action otherwise.
+    fn draw_timeline(&self) {
+        let current = self.processed_records.len().checked_sub(1);
+        let records = self
+            .processed_records
+            .iter()
+            .chain(self.records.iter())
+            .collect::<Vec<_>>();
+
+        let focus = match self.timeline.focused {
+            true => self.timeline.selected,
+            false => current.unwrap_or(0),
+        };
+
+        let start = focus
+            .saturating_sub(TIMELINE_WINDOW / 2)
+            .min(records.len().saturating_sub(TIMELINE_WINDOW));
+
+        let width = screen_width() * TIMELINE_WIDTH;
+        let x = screen_width() - width;
+
+        DrawCommand::rectangle(x, 0.0, width, screen_height())
+            .color(BLACK.with_alpha(0.6))
+            .z_index(ZIndex::ModalBackground)
+            .ignore_padding()
+            .schedule();
+
+        for (row, (index, record)) in records
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(TIMELINE_WINDOW)
+            .enumerate()
+        {
+            // Played actions are white, the current one is yellow.
+            let color = match current {
+                Some(current) if index == current => YELLOW,
+                Some(current) if index < current => WHITE,
+                _ => GRAY,
+            };
+
+            let cursor = match self.timeline.focused && index == self.timeline.selected {
+                true => "> ",
+                false => "  ",
+            };
+
+            DrawCommand::text(format!("{}{}", cursor, timeline_entry(record)))
+                .position(x + 10.0, 30.0 + row as f32 * FONT_SIZE * 1.2)
+                .font_size(FONT_SIZE as u16)
+                .color(color)
+                .z_index(ZIndex::ModalText)
+                .schedule();
+        }
+    }
+}
+
+/// Format a single entry of the timeline: turns are shown as separators, the
+/// rest of the actions are listed with their effects.
+fn timeline_entry(record: &ProcessedRecord) -> String {
+    match record {
+        ProcessedRecord::NextTurn(turn) => format!("--- Turn {} ---", turn),
+        ProcessedRecord::Attack { effects, .. } | ProcessedRecord::Grenade { effects, .. } => {
+            let effects = effects
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            format!("{}: {}", record, effects)
+        }
+        _ => record.to_string(),
+    }
+}
+
+impl Playback {
+    /// Speed multiplier of the playback.
+    pub fn multiplier(&self) -> f64 {
+        PLAYBACK_SPEEDS[self.speed]
+    }
+
+    pub fn toggle(&mut self) {
+        self.playing = !self.playing;
+        self.idle_since = None;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+        self.idle_since = None;
+    }
+
+    pub fn speed_up(&mut self) {
+        self.speed = (self.speed + 1).min(PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            playing: false,
+            speed: 1,
+            idle_since: None,
         }
     }
 }
@@ -455,8 +936,8 @@ impl History {
                     target: (target[0] as u8, target[1] as u8),
                     effects: records,
                 },
-                Record::Grenade(radius, x, y) => ProcessedRecord::Grenade {
-                    radius: radius,
+                Record::Grenade(x, y, radius) => ProcessedRecord::Grenade {
+                    radius,
                     target: (x as u8, y as u8),
                     effects: records,
                 },
@@ -578,3 +1059,40 @@ fn test_history() {
 
     assert!(history.is_empty());
 }
+
+#[test]
+fn test_playback_speed() {
+    let mut playback = Playback::default();
+    assert_eq!(playback.multiplier(), 1.0);
+
+    playback.slow_down();
+    playback.slow_down();
+    assert_eq!(playback.multiplier(), 0.5);
+
+    (0..5).for_each(|_| playback.speed_up());
+    assert_eq!(playback.multiplier(), 4.0);
+
+    playback.toggle();
+    assert!(playback.playing);
+    playback.pause();
+    assert!(!playback.playing);
+}
+
+#[test]
+fn test_timeline_entry() {
+    let attack = ProcessedRecord::Attack {
+        origin: (0, 0),
+        target: (0, 1),
+        effects: vec![Record::CriticalHit(6), Record::UnitKIA(ID::default())],
+    };
+
+    assert_eq!(
+        timeline_entry(&attack),
+        format!("Attack: Critical Hit: 6, Unit KIA: {}", ID::default())
+    );
+    assert_eq!(
+        timeline_entry(&ProcessedRecord::NextTurn(3)),
+        "--- Turn 3 ---"
+    );
+    assert_eq!(timeline_entry(&ProcessedRecord::Reload((1, 2))), "Reload");
+}
@@ -0,0 +1,329 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! After-action report: statistics of every unit computed from the `History`
+//! of a game. Units are told apart by the order of the `RecruitPlaced` records
+//! and followed through the game by their position.
+
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+use super::ProcessedRecord;
+use crate::{
+    draw::{DrawCommand, ZIndex, draw},
+    game::AppComponent,
+    input::InputCommand,
+    types::{History, ID, Record, Roster, grenade_area},
+};
+
+/// Statistics of a single unit over the game.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnitReport {
+    /// ID of the recruit, known for the units KIA or identified in a `Roster`.
+    pub id: Option<ID>,
+    pub shots: u16,
+    pub hits: u16,
+    pub crits: u16,
+    /// Attacks against the unit which it dodged.
+    pub dodges: u16,
+    /// Damage dealt with attacks. Grenade records don't store who threw the
+    /// grenade, so grenade damage only counts as received.
+    pub damage_dealt: u16,
+    pub damage_received: u16,
+    pub tiles_moved: u16,
+    /// Units KIA by the attacks of this unit.
+    pub kills: u16,
+    pub kia: bool,
+    /// Position at the end of the game, `None` if the unit is KIA.
+    pub position: Option<(u8, u8)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Units in the order of placement.
+    pub units: Vec<UnitReport>,
+    /// The last turn of the game.
+    pub turns: u16,
+}
+
+impl UnitReport {
+    /// Share of the shots which hit, in percent.
+    pub fn hit_rate(&self) -> u16 {
+        match self.shots {
+            0 => 0,
+            shots => self.hits * 100 / shots,
+        }
+    }
+}
+
+impl Report {
+    pub fn new<'a>(records: impl Iterator<Item = &'a ProcessedRecord>) -> Self {
+        let mut report = Self::default();
+        // Positions of the units on the map and their placement index.
+        let mut positions: HashMap<(u8, u8), usize> = HashMap::new();
+
+        for record in records {
+            match record {
+                ProcessedRecord::RecruitPlaced(x, y) => {
+                    positions.insert((*x, *y), report.units.len());
+                    report.units.push(UnitReport::default());
+                }
+                ProcessedRecord::NextTurn(turn) => report.turns = *turn,
+                ProcessedRecord::Reload(_) => {}
+                ProcessedRecord::Move(coords, _) => {
+                    if let (Some(start), Some(end)) = (coords.first(), coords.last())
+                        && let Some(index) = positions.remove(start)
+                    {
+                        report.units[index].tiles_moved += coords.len() as u16 - 1;
+                        positions.insert(*end, index);
+                    }
+                }
+                ProcessedRecord::Attack {
+                    origin,
+                    target,
+                    effects,
+                } => {
+                    let attacker = positions.get(origin).copied();
+                    let defender = positions.get(target).copied();
+
+                    if let Some(index) = attacker {
+                        report.units[index].shots += 1;
+                    }
+
+                    for effect in effects {
+                        match effect {
+                            Record::Damage(damage) | Record::CriticalHit(damage) => {
+                                if let Some(index) = attacker {
+                                    let unit = &mut report.units[index];
+                                    unit.hits += 1;
+                                    unit.damage_dealt += *damage as u16;
+                                    if let Record::CriticalHit(_) = effect {
+                                        unit.crits += 1;
+                                    }
+                                }
+
+                                if let Some(index) = defender {
+                                    report.units[index].damage_received += *damage as u16;
+                                }
+                            }
+                            Record::Dodged => {
+                                if let Some(index) = defender {
+                                    report.units[index].dodges += 1;
+                                }
+                            }
+                            Record::UnitKIA(id) => {
+                                if let Some(index) = attacker {
+                                    report.units[index].kills += 1;
+                                }
+
+                                if let Some(index) = positions.remove(target) {
+                                    report.units[index].kia = true;
+                                    report.units[index].id = Some(*id);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                ProcessedRecord::Grenade {
+                    radius,
+                    target,
+                    effects,
+                } => {
+                    let mut effects = effects.iter().peekable();
+
+                    // Every unit in the area gets a `Damage` record, followed
+                    // by `UnitKIA` if the unit died.
+                    for position in grenade_area(*target, *radius as u8) {
+                        let Some(index) = positions.get(&position).copied() else {
+                            continue;
+                        };
+
+                        if let Some(Record::Damage(damage)) =
+                            effects.next_if(|e| matches!(e, Record::Damage(_)))
+                        {
+                            report.units[index].damage_received += *damage as u16;
+                        }
+
+                        if let Some(Record::UnitKIA(id)) = effects.peek() {
+                            positions.remove(&position);
+                            report.units[index].kia = true;
+                            report.units[index].id = Some(*id);
+                            effects.next();
+                        }
+                    }
+                }
+            }
+        }
+
+        for (position, index) in positions {
+            report.units[index].position = Some(position);
+        }
+
+        report
+    }
+
+    pub fn from_history(history: &History) -> Self {
+        let records = history.clone().take_records_with_effects();
+        Self::new(records.iter())
+    }
+
+    /// Identify the units which survived the game by their final position.
+    pub fn identify(&mut self, roster: &Roster) {
+        for unit in self.units.iter_mut().filter(|u| u.id.is_none()) {
+            unit.id = unit.position.and_then(|p| roster.survivor(p));
+        }
+    }
+}
+
+/// Shows the `Report` of a finished game or a replay.
+pub struct ReportScreen {
+    pub report: Report,
+    /// Used to show the names of the recruits.
+    pub roster: Roster,
+}
+
+pub enum ReportMessage {
+    Exit,
+    None,
+}
+
+impl ReportScreen {
+    pub fn new(report: Report, roster: Roster) -> Self {
+        let mut screen = Self {
+            report,
+            roster: Roster::default(),
+        };
+
+        screen.set_roster(roster);
+        screen
+    }
+
+    pub fn set_roster(&mut self, roster: Roster) {
+        self.report.identify(&roster);
+        self.roster = roster;
+    }
+
+    /// Name of the unit with the placement index.
+    fn unit_name(&self, index: usize, unit: &UnitReport) -> String {
+        unit.id
+            .and_then(|id| self.roster.recruit(&id))
+            .map(|r| r.metadata.name.clone())
+            .unwrap_or(format!("Unit {}", index + 1))
+    }
+}
+
+impl AppComponent for ReportScreen {
+    type Message = ReportMessage;
+
+    fn handle_key_press(&mut self, key: InputCommand) -> Self::Message {
+        match key {
+            InputCommand::Menu | InputCommand::Select | InputCommand::Back => ReportMessage::Exit,
+            _ => ReportMessage::None,
+        }
+    }
+
+    fn tick(&mut self) {
+        draw::draw_main_menu_background();
+
+        let mut text = format!(
+            "After-Action Report\nTurns: {}\n\n{:<12}{:>7}{:>7}{:>7}{:>8}{:>8}{:>7}{:>7}{:>7}\n",
+            self.report.turns,
+            "Unit",
+            "Shots",
+            "Hit %",
+            "Crits",
+            "Dodges",
+            "Dealt",
+            "Taken",
+            "Moved",
+            "Kills"
+        );
+
+        for (index, unit) in self.report.units.iter().enumerate() {
+            let name = match unit.kia {
+                true => format!("{} (KIA)", self.unit_name(index, unit)),
+                false => self.unit_name(index, unit),
+            };
+
+            text += &format!(
+                "{:<12}{:>7}{:>7}{:>7}{:>8}{:>8}{:>7}{:>7}{:>7}\n",
+                name,
+                unit.shots,
+                unit.hit_rate(),
+                unit.crits,
+                unit.dodges,
+                unit.damage_dealt,
+                unit.damage_received,
+                unit.tiles_moved,
+                unit.kills,
+            );
+        }
+
+        DrawCommand::text(text)
+            .position(40.0, 80.0)
+            .font_size(20)
+            .color(WHITE)
+            .z_index(ZIndex::MenuText)
+            .schedule();
+    }
+}
+
+#[test]
+fn test_report() {
+    let kia = ID::default();
+    let records = [
+        ProcessedRecord::RecruitPlaced(0, 0),
+        ProcessedRecord::RecruitPlaced(0, 5),
+        ProcessedRecord::RecruitPlaced(4, 4),
+        ProcessedRecord::NextTurn(1),
+        ProcessedRecord::Move(vec![(0, 0), (0, 1), (0, 2)], vec![]),
+        ProcessedRecord::Attack {
+            origin: (0, 2),
+            target: (0, 5),
+            effects: vec![Record::Miss],
+        },
+        ProcessedRecord::Attack {
+            origin: (0, 2),
+            target: (0, 5),
+            effects: vec![Record::Dodged],
+        },
+        ProcessedRecord::Attack {
+            origin: (0, 2),
+            target: (0, 5),
+            effects: vec![Record::CriticalHit(9), Record::UnitKIA(kia)],
+        },
+        ProcessedRecord::NextTurn(2),
+        ProcessedRecord::Grenade {
+            radius: 1,
+            target: (4, 5),
+            effects: vec![Record::Damage(3)],
+        },
+    ];
+
+    let report = Report::new(records.iter());
+    let (shooter, target, survivor) = (&report.units[0], &report.units[1], &report.units[2]);
+
+    assert_eq!(report.turns, 2);
+    assert_eq!((shooter.shots, shooter.hits, shooter.crits), (3, 1, 1));
+    assert_eq!(shooter.hit_rate(), 33);
+    assert_eq!((shooter.damage_dealt, shooter.kills), (9, 1));
+    assert_eq!((shooter.tiles_moved, shooter.position), (2, Some((0, 2))));
+    assert_eq!((target.dodges, target.damage_received), (1, 9));
+    assert_eq!(
+        (target.kia, target.id, target.position),
+        (true, Some(kia), None)
+    );
+    assert_eq!((survivor.damage_received, survivor.kia), (3, false));
+
+    let mut report = report.clone();
+    let id = ID(sui_sdk_types::Address::from_bytes([1; 32]).unwrap());
+    report.identify(&Roster {
+        recruits: vec![],
+        survivors: vec![((0, 2), id)],
+    });
+    assert_eq!(report.units[0].id, Some(id));
+    assert_eq!(report.units[1].id, Some(kia));
+    assert_eq!(report.units[2].id, None);
+}
@@ -46,6 +46,12 @@ impl SettingsScreen {
     }
 }
 
+impl Default for SettingsScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AppComponent for SettingsScreen {
     type Message = SettingsScreenMessage;
 
@@ -148,6 +154,12 @@ impl Menu<SettingsScreenItem> {
     }
 }
 
+impl Default for Menu<SettingsScreenItem> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Display for SettingsScreenItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let settings = Settings::load();
@@ -0,0 +1,139 @@
+#![allow(dead_code)]
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Commander game client. The library is shared by the game window in
+//! `main.rs` and the headless `commander-cli`.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use sui_rpc::proto::sui::rpc::v2::Object;
+use sui_sdk_types::{Address, Digest, ObjectReference, Version, ZkLoginInputs};
+
+pub mod config;
+pub mod draw;
+pub mod errors;
+pub mod game;
+pub mod input;
+pub mod rules;
+pub mod settings;
+pub mod sound;
+pub mod sui;
+pub mod types;
+
+use crate::types::{Armor, Game, History, Host, Preset, Recruit, Replay, Roster, Weapon};
+
+/// Messages sent from the tokio runtime to the Application.
+pub enum Message {
+    Text(String),
+    StateUpdated,
+    GameStarted,
+    LoginStarted,
+    LoginFinished,
+    GameDestroyed,
+    PlayEffects(History),
+    /// Game fetched to show its spawns before joining.
+    HostedGame(Box<Game>),
+    /// Recruits who took part in the game of the opened replay.
+    ReplayRoster(Roster),
+}
+
+/// Storage key of the login `Session`.
+pub const SESSION_KEY: &str = "session";
+
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    pub address: Address,
+    pub keypair: String,
+    pub zkp: ZkLoginInputs,
+    pub max_epoch: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+/// The state of the application, consists of fetched data from the server.
+/// Passed into the `App` during initialization and filled in + updated in event
+/// handlers.
+pub struct State {
+    pub address: Option<Address>,
+    pub presets: Vec<WithRef<Preset>>,
+    pub recruits: Vec<WithRef<Recruit>>,
+    /// Owned weapons which are not equipped by any recruit.
+    pub weapons: Vec<WithRef<Weapon>>,
+    /// Owned armor which is not equipped by any recruit.
+    pub armor: Vec<WithRef<Armor>>,
+    pub replays: Vec<WithRef<Replay>>,
+    pub hosted_games: Vec<WithRef<Host>>,
+    pub active_game: Option<Game>,
+    /// Recruits placed in the active game, used to tell own units apart.
+    pub game_recruits: Vec<Recruit>,
+}
+
+// TODO: configure to work only for desktop builds. We needn't do it on mobile or wasm builds.
+pub fn init_storage_dir() {
+    use dirs;
+    use std::env;
+    use std::fs;
+
+    // Detect platform-appropriate data dir
+    let mut dir = dirs::data_dir().unwrap_or_else(|| env::current_dir().unwrap());
+    dir.push(env!("CARGO_PKG_NAME")); // uses your crate name as folder
+
+    // Ensure it exists
+    fs::create_dir_all(&dir).unwrap_or_else(|e| {
+        eprintln!("Could not create storage directory {:?}: {}", dir, e);
+    });
+
+    // Change current working directory to it,
+    // so Macroquad's save/load functions use it implicitly
+    env::set_current_dir(&dir).unwrap_or_else(|e| {
+        eprintln!("Could not set current dir to {:?}: {}", dir, e);
+    });
+
+    println!("Storage directory set to: {:?}", dir);
+}
+
+// === Utils ===
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithRef<T> {
+    pub object_ref: ObjectReference,
+    pub data: T,
+}
+
+impl<'de, T> WithRef<T> {
+    pub fn from_rpc_object(obj: &'de Object) -> Result<Self, anyhow::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        Ok(WithRef {
+            data: bcs::from_bytes(
+                obj.contents
+                    .as_ref()
+                    .ok_or(anyhow::anyhow!("No contents"))?
+                    .value(),
+            )?,
+            object_ref: ObjectReference::new(
+                Address::from_str(&obj.object_id()).unwrap(),
+                Version::from(obj.version()),
+                Digest::from_base58(&obj.digest()).unwrap(),
+            ),
+        })
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            address: None,
+            presets: vec![],
+            recruits: vec![],
+            weapons: vec![],
+            armor: vec![],
+            replays: vec![],
+            hosted_games: vec![],
+            active_game: None,
+            game_recruits: vec![],
+        }
+    }
+}
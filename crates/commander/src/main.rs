@@ -1,101 +1,40 @@
-#![allow(dead_code)]
-
 // Copyright (c) Sui Potatoes
 // SPDX-License-Identifier: MIT
 
 use std::{
-    str::FromStr,
     sync::{
         Arc, Mutex,
         mpsc::{Receiver, Sender, channel},
     },
+    time::{Duration, Instant},
 };
 
 use gamepads::Gamepads;
 use macroquad::{miniquad::conf::Icon, prelude::*};
 use quad_storage::STORAGE;
-use serde::{Deserialize, Serialize};
 
 use sui_crypto::ed25519::Ed25519PrivateKey;
-use sui_rpc::{Client, proto::sui::rpc::v2::Object};
-use sui_sdk_types::{Address, Digest, Ed25519PublicKey, ObjectReference, Version, ZkLoginInputs};
+use sui_rpc::Client;
+use sui_sdk_types::{Address, Ed25519PublicKey, ZkLoginInputs};
 use tokio::runtime::Runtime;
 
-mod config;
-mod draw;
-mod errors;
-mod game;
-mod input;
-mod settings;
-mod sound;
-mod sui;
-mod types;
-
-use crate::{
-    draw::{ASSETS, AssetStore},
-    game::{App, Message as AppMessage, PlayMessage},
+use commander::{
+    Message, SESSION_KEY, Session, State, WithRef,
+    config::GRPC_URL,
+    draw::{self, ASSETS, AssetStore},
+    game::{self, App, LoadoutAction, Message as AppMessage, PlayMessage},
+    init_storage_dir, input,
     settings::Settings,
     sound::{Background, SOUNDS, SoundStore},
-    sui::{fetch::GameClient, tx::TxExecutor},
-    types::{Game, History, Preset, Recruit, Replay},
+    sui::{self, fetch::GameClient, tx::TxExecutor},
+    types::{Game, History, ID, Preset, REPLAYS_DIR, Recruit, ReplayFile, Roster},
 };
 
-/// Messages sent from the tokio runtime to the Application.
-pub enum Message {
-    Text(String),
-    StateUpdated,
-    GameStarted,
-    LoginStarted,
-    LoginFinished,
-    GameDestroyed,
-    PlayEffects(History),
-}
-
-const SESSION_KEY: &str = "session";
-
-#[derive(Serialize, Deserialize)]
-pub struct Session {
-    pub address: Address,
-    pub keypair: String,
-    pub zkp: ZkLoginInputs,
-    pub max_epoch: u64,
-}
-
-#[derive(Serialize, Deserialize)]
-/// The state of the application, consists of fetched data from the server.
-/// Passed into the `App` during initialization and filled in + updated in event
-/// handlers.
-pub struct State {
-    pub address: Option<Address>,
-    pub presets: Vec<WithRef<Preset>>,
-    pub recruits: Vec<WithRef<Recruit>>,
-    pub replays: Vec<WithRef<Replay>>,
-    pub active_game: Option<Game>,
-}
-
-// TODO: configure to work only for desktop builds. We needn't do it on mobile or wasm builds.
-fn init_storage_dir() {
-    use dirs;
-    use std::env;
-    use std::fs;
-
-    // Detect platform-appropriate data dir
-    let mut dir = dirs::data_dir().unwrap_or_else(|| env::current_dir().unwrap());
-    dir.push(env!("CARGO_PKG_NAME")); // uses your crate name as folder
+/// Stores the rosters of the finished games along with their history.
+const ROSTERS_KEY: &str = "rosters";
 
-    // Ensure it exists
-    fs::create_dir_all(&dir).unwrap_or_else(|e| {
-        eprintln!("Could not create storage directory {:?}: {}", dir, e);
-    });
-
-    // Change current working directory to it,
-    // so Macroquad's save/load functions use it implicitly
-    env::set_current_dir(&dir).unwrap_or_else(|e| {
-        eprintln!("Could not set current dir to {:?}: {}", dir, e);
-    });
-
-    println!("Storage directory set to: {:?}", dir);
-}
+/// Number of the most recent rosters kept in the storage.
+const MAX_ROSTERS: usize = 20;
 
 /// Configure Macroquad on start.
 fn window_conf() -> macroquad::window::Conf {
@@ -171,8 +110,8 @@ async fn main() -> Result<(), anyhow::Error> {
     }
 }
 
-const GRPC_URL: &str =
-    "https://fullnode.testnet.sui.io/sui.rpc.v2beta2.LiveDataService/SimulateTransaction";
+/// How often the active game is fetched to pick up the opponent's actions.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: Arc<Mutex<State>>) {
     let default_provider = rustls::crypto::aws_lc_rs::default_provider();
@@ -186,6 +125,12 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
         let mut game_client = GameClient::new(client.clone());
         let mut tx_runner: Option<TxExecutor> = None;
 
+        // The active game is polled for records which were not emitted by our
+        // own transactions, eg. actions of the opponent.
+        let mut watched_game: Option<Address> = None;
+        let mut applied_records = 0;
+        let mut last_poll = Instant::now();
+
         if let Some(prev_session) = prev_session {
             if let Ok(session) = serde_json::from_str::<Session>(&prev_session) {
                 // If the session is expired, remove it and show login screen.
@@ -249,7 +194,7 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
 
                         tx.send(Message::LoginFinished).unwrap();
                     }
-                    AppMessage::StartGame => {
+                    AppMessage::StartGame(preset_id, squad) => {
                         // Fetch presets if they are not already fetched.
                         let mut state = state_arc.lock().unwrap();
                         if state.presets.is_empty() {
@@ -264,8 +209,11 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
 
                             state.active_game = game;
 
-                            if let Some(_game) = &state.active_game {
+                            if let Some(game) = &state.active_game {
                                 println!("Game already started");
+                                watched_game = Some(game.id.into());
+                                applied_records = game.history.0.len();
+                                state.game_recruits = game_client.list_game_recruits(game).await;
                                 tx.send(Message::GameStarted).unwrap();
                                 continue;
                             } else {
@@ -279,18 +227,34 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                             continue;
                         }
 
+                        let preset = preset_id
+                            .and_then(|id| state.presets.iter().find(|p| p.data.id == id).cloned());
+
+                        let Some(preset) = preset else {
+                            eprintln!("No preset selected");
+                            tx.send(Message::GameDestroyed).unwrap();
+                            continue;
+                        };
+
+                        let spawns = preset.data.positions.len();
+                        let squad = resolve_squad(&state.recruits, &squad, spawns);
+
                         if let Some(tx_runner) = tx_runner.as_mut() {
                             println!("Starting new game");
-                            match tx_runner.start_game(state.presets[0].clone()).await {
+                            match tx_runner.start_game(preset, squad.clone()).await {
                                 Ok(game_id) => {
                                     println!("https://suiscan.xyz/testnet/object/{}", game_id);
                                     let game = game_client.get_game(game_id).await.ok();
                                     println!("Fetched game {}", game_id);
 
+                                    remove_placed(&mut state.recruits, &squad);
+
                                     state.active_game = game;
 
                                     if let Some(game) = &state.active_game {
                                         println!("Game started");
+                                        watched_game = Some(game_id);
+                                        applied_records = game.history.0.len();
                                         tx.send(Message::GameStarted).unwrap();
                                         STORAGE.lock().unwrap().set(
                                             "active_game",
@@ -307,27 +271,324 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                             }
                         }
                     }
+                    AppMessage::HireRecruit => {
+                        let Some(tx_runner) = tx_runner.as_mut() else {
+                            continue;
+                        };
+
+                        let name = game::random_recruit_name();
+                        let backstory = format!("{} joined the squad as a rookie.", name);
+
+                        println!("Hiring recruit: {}", name);
+                        match tx_runner.hire_recruit(name, backstory).await {
+                            Ok((effects, _events)) => println!("Result: {:?}", effects.status),
+                            Err(err) => {
+                                eprintln!("Error: {}", err);
+                                continue;
+                            }
+                        }
+
+                        // Refresh recruits and inventory to reflect the change.
+                        let Some(address) = state_arc.lock().unwrap().address else {
+                            continue;
+                        };
+
+                        refresh_inventory(&mut game_client, address, &state_arc).await;
+                        tx.send(Message::StateUpdated).unwrap();
+                    }
+                    AppMessage::DismissRecruit(recruit_id) => {
+                        let Some(tx_runner) = tx_runner.as_mut() else {
+                            continue;
+                        };
+
+                        let recruit = state_arc
+                            .lock()
+                            .unwrap()
+                            .recruits
+                            .iter()
+                            .find(|r| r.data.id == recruit_id)
+                            .cloned();
+
+                        let Some(recruit) = recruit else {
+                            eprintln!("Recruit {} not found", recruit_id);
+                            continue;
+                        };
+
+                        println!("Dismissing recruit: {}", recruit.data.metadata.name);
+                        match tx_runner.dismiss_recruit(recruit).await {
+                            Ok((effects, _events)) => println!("Result: {:?}", effects.status),
+                            Err(err) => {
+                                eprintln!("Error: {}", err);
+                                continue;
+                            }
+                        }
+
+                        // Refresh recruits and inventory to reflect the change.
+                        let Some(address) = state_arc.lock().unwrap().address else {
+                            continue;
+                        };
+
+                        refresh_inventory(&mut game_client, address, &state_arc).await;
+                        tx.send(Message::StateUpdated).unwrap();
+                    }
+                    AppMessage::UpdateLoadout(recruit_id, action) => {
+                        let Some(tx_runner) = tx_runner.as_mut() else {
+                            continue;
+                        };
+
+                        let (recruit, other) = {
+                            let state = state_arc.lock().unwrap();
+                            let find =
+                                |id: ID| state.recruits.iter().find(|r| r.data.id == id).cloned();
+                            let other = match action {
+                                LoadoutAction::Swap(_, other_id) => find(other_id),
+                                _ => None,
+                            };
+
+                            (find(recruit_id), other)
+                        };
+
+                        let Some(recruit) = recruit else {
+                            eprintln!("Recruit {} not found", recruit_id);
+                            continue;
+                        };
+
+                        println!(
+                            "Updating loadout of {}: {:?}",
+                            recruit.data.metadata.name, action
+                        );
+                        let result = match action {
+                            LoadoutAction::Equip(slot, item) => {
+                                tx_runner.equip_item(recruit, slot, item).await
+                            }
+                            LoadoutAction::Unequip(slot) => {
+                                tx_runner.unequip_item(recruit, slot).await
+                            }
+                            LoadoutAction::Swap(slot, other_id) => match other {
+                                Some(other) => tx_runner.swap_items(recruit, other, slot).await,
+                                None => Err(anyhow::anyhow!("Recruit {} not found", other_id)),
+                            },
+                            LoadoutAction::AddUpgrade(kind, tier) => {
+                                tx_runner.add_weapon_upgrade(recruit, kind, tier).await
+                            }
+                            LoadoutAction::RemoveUpgrade(index) => {
+                                tx_runner.remove_weapon_upgrade(recruit, index).await
+                            }
+                        };
+
+                        match result {
+                            Ok((effects, _events)) => println!("Result: {:?}", effects.status),
+                            Err(err) => {
+                                eprintln!("Error: {}", err);
+                                continue;
+                            }
+                        }
+
+                        // Refresh recruits and inventory to reflect the change.
+                        let Some(address) = state_arc.lock().unwrap().address else {
+                            continue;
+                        };
+
+                        refresh_inventory(&mut game_client, address, &state_arc).await;
+                        tx.send(Message::StateUpdated).unwrap();
+                    }
+                    AppMessage::FetchInventory => {
+                        let Some(address) = state_arc.lock().unwrap().address else {
+                            continue;
+                        };
+
+                        let weapons = game_client.list_weapons(address).await;
+                        let armor = game_client.list_armor(address).await;
+
+                        let mut state = state_arc.lock().unwrap();
+                        state.weapons = weapons;
+                        state.armor = armor;
+                        tx.send(Message::StateUpdated).unwrap();
+                    }
+                    AppMessage::PublishPreset(preset) => {
+                        let Some(tx_runner) = tx_runner.as_mut() else {
+                            continue;
+                        };
+
+                        let Preset {
+                            name,
+                            map,
+                            positions,
+                            ..
+                        } = preset;
+
+                        println!("Publishing map: {}", name);
+                        match tx_runner.publish_map(name, &map, &positions).await {
+                            Ok((effects, _events)) => println!("Result: {:?}", effects.status),
+                            Err(err) => {
+                                eprintln!("Error: {}", err);
+                                continue;
+                            }
+                        }
+
+                        // Refresh presets so the new one is available to play.
+                        let presets = game_client.list_presets().await;
+                        STORAGE
+                            .lock()
+                            .unwrap()
+                            .set("presets", &serde_json::to_string(&presets).unwrap());
+                        state_arc.lock().unwrap().presets = presets;
+                        tx.send(Message::StateUpdated).unwrap();
+                    }
+                    AppMessage::FetchHostedGames => {
+                        let hosted_games = game_client.list_hosted_games().await;
+                        let fetch_presets = state_arc.lock().unwrap().presets.is_empty();
+                        let presets = match fetch_presets {
+                            true => Some(game_client.list_presets().await),
+                            false => None,
+                        };
+
+                        let mut state = state_arc.lock().unwrap();
+                        state.hosted_games = hosted_games;
+                        if let Some(presets) = presets {
+                            state.presets = presets;
+                        }
+
+                        tx.send(Message::StateUpdated).unwrap();
+                    }
+                    AppMessage::FetchGame(game_id) => {
+                        match game_client.get_game(game_id.into()).await {
+                            Ok(game) => tx.send(Message::HostedGame(Box::new(game))).unwrap(),
+                            Err(err) => eprintln!("Error: {}", err),
+                        }
+                    }
+                    AppMessage::HostGame(preset_id, squad) => {
+                        let Some(tx_runner) = tx_runner.as_mut() else {
+                            continue;
+                        };
+
+                        let (preset, squad) = {
+                            let state = state_arc.lock().unwrap();
+                            let preset = state
+                                .presets
+                                .iter()
+                                .find(|p| p.data.id == preset_id)
+                                .cloned();
+
+                            // The host places the first half of the recruits, the
+                            // rest is left for the player joining the game.
+                            let spawns = preset
+                                .as_ref()
+                                .map_or(0, |p| p.data.positions.len().div_ceil(2));
+
+                            (preset, resolve_squad(&state.recruits, &squad, spawns))
+                        };
+
+                        let Some(preset) = preset else {
+                            eprintln!("Preset {} not found", preset_id);
+                            tx.send(Message::GameDestroyed).unwrap();
+                            continue;
+                        };
+
+                        println!("Hosting new game");
+                        match tx_runner.host_game(preset, squad.clone()).await {
+                            Ok(game_id) => {
+                                println!("https://suiscan.xyz/testnet/object/{}", game_id);
+                                let game = game_client.get_game(game_id).await.ok();
+
+                                if let Some(game) = game {
+                                    println!("Game hosted");
+                                    watched_game = Some(game_id);
+                                    applied_records = game.history.0.len();
+                                    let recruits = game_client.list_game_recruits(&game).await;
+                                    let mut state = state_arc.lock().unwrap();
+                                    remove_placed(&mut state.recruits, &squad);
+                                    state.game_recruits = recruits;
+                                    STORAGE
+                                        .lock()
+                                        .unwrap()
+                                        .set("active_game", &serde_json::to_string(&game).unwrap());
+                                    state.active_game = Some(game);
+                                    tx.send(Message::GameStarted).unwrap();
+                                }
+                            }
+                            Err(err) => {
+                                eprintln!("Error: {}", err);
+                                tx.send(Message::GameDestroyed).unwrap();
+                            }
+                        }
+                    }
+                    AppMessage::JoinGame(host, squad) => {
+                        let Some(tx_runner) = tx_runner.as_mut() else {
+                            continue;
+                        };
+
+                        // Take all of the spawn positions left by the host.
+                        let game_id = host.data.game_id.into();
+                        let spawns = match game_client.get_game(game_id).await {
+                            Ok(game) => game.positions.len(),
+                            Err(err) => {
+                                eprintln!("Error: {}", err);
+                                tx.send(Message::GameDestroyed).unwrap();
+                                continue;
+                            }
+                        };
+
+                        let squad =
+                            resolve_squad(&state_arc.lock().unwrap().recruits, &squad, spawns);
+
+                        println!("Joining game {}", game_id);
+                        match tx_runner.join_game(host, squad.clone()).await {
+                            Ok((effects, _events)) => {
+                                println!("Result: {:?}", effects.status);
+                                let game = game_client.get_game(game_id).await.ok();
+
+                                if let Some(game) = game {
+                                    println!("Game joined");
+                                    watched_game = Some(game_id);
+                                    applied_records = game.history.0.len();
+                                    let recruits = game_client.list_game_recruits(&game).await;
+                                    let mut state = state_arc.lock().unwrap();
+                                    remove_placed(&mut state.recruits, &squad);
+                                    state.game_recruits = recruits;
+                                    STORAGE
+                                        .lock()
+                                        .unwrap()
+                                        .set("active_game", &serde_json::to_string(&game).unwrap());
+                                    state.active_game = Some(game);
+                                    tx.send(Message::GameStarted).unwrap();
+                                }
+                            }
+                            Err(err) => {
+                                eprintln!("Error: {}", err);
+                                tx.send(Message::GameDestroyed).unwrap();
+                            }
+                        }
+                    }
                     AppMessage::Play(message) => match message {
-                        PlayMessage::Move(path) => {
-                            println!("Moving unit: {:?}", path);
+                        PlayMessage::Move(_) | PlayMessage::Dash(..) => {
+                            let paths = message.move_legs();
+                            println!("Moving unit: {:?}", paths);
                             let game_id =
                                 state_arc.lock().unwrap().active_game.as_ref().unwrap().id;
-                            let (effects, events) = tx_runner
+                            let result = tx_runner
                                 .as_mut()
                                 .unwrap()
-                                .move_unit(game_id.into(), path)
-                                .await
-                                .unwrap();
+                                .move_unit_legs(game_id.into(), &paths)
+                                .await;
+
+                            let (effects, events) = match result {
+                                Ok(result) => result,
+                                Err(err) => {
+                                    eprintln!("Failed to move unit: {}", err);
+                                    continue;
+                                }
+                            };
 
                             println!("Result: {:?}", effects.status);
 
                             if let Some(events) = events {
                                 events.0.iter().for_each(|event| {
                                     // we know all events in this transaction are from Commander
-                                    tx.send(Message::PlayEffects(
-                                        bcs::from_bytes(event.contents.as_ref()).unwrap(),
-                                    ))
-                                    .unwrap();
+                                    let history: History =
+                                        bcs::from_bytes(event.contents.as_ref()).unwrap();
+                                    applied_records += history.0.len();
+                                    tx.send(Message::PlayEffects(history)).unwrap();
                                 });
                             }
                         }
@@ -347,10 +608,33 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                             if let Some(events) = events {
                                 events.0.iter().for_each(|event| {
                                     // we know all events in this transaction are from Commander
-                                    tx.send(Message::PlayEffects(
-                                        bcs::from_bytes(event.contents.as_ref()).unwrap(),
-                                    ))
-                                    .unwrap();
+                                    let history: History =
+                                        bcs::from_bytes(event.contents.as_ref()).unwrap();
+                                    applied_records += history.0.len();
+                                    tx.send(Message::PlayEffects(history)).unwrap();
+                                });
+                            }
+                        }
+                        PlayMessage::Grenade(p0, p1) => {
+                            println!("Throwing grenade: {:?}, {:?}", p0, p1);
+                            let game_id =
+                                state_arc.lock().unwrap().active_game.as_ref().unwrap().id;
+                            let (effects, events) = tx_runner
+                                .as_mut()
+                                .unwrap()
+                                .perform_grenade(game_id.into(), p0, p1)
+                                .await
+                                .unwrap();
+
+                            println!("Result: {:?}", effects.status);
+
+                            if let Some(events) = events {
+                                events.0.iter().for_each(|event| {
+                                    // we know all events in this transaction are from Commander
+                                    let history: History =
+                                        bcs::from_bytes(event.contents.as_ref()).unwrap();
+                                    applied_records += history.0.len();
+                                    tx.send(Message::PlayEffects(history)).unwrap();
                                 });
                             }
                         }
@@ -370,10 +654,10 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                             if let Some(events) = events {
                                 events.0.iter().for_each(|event| {
                                     // we know all events in this transaction are from Commander
-                                    tx.send(Message::PlayEffects(
-                                        bcs::from_bytes(event.contents.as_ref()).unwrap(),
-                                    ))
-                                    .unwrap();
+                                    let history: History =
+                                        bcs::from_bytes(event.contents.as_ref()).unwrap();
+                                    applied_records += history.0.len();
+                                    tx.send(Message::PlayEffects(history)).unwrap();
                                 });
                             }
                         }
@@ -391,10 +675,10 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                             if let Some(events) = events {
                                 events.0.iter().for_each(|event| {
                                     // we know all events in this transaction are from Commander
-                                    tx.send(Message::PlayEffects(
-                                        bcs::from_bytes(event.contents.as_ref()).unwrap(),
-                                    ))
-                                    .unwrap();
+                                    let history: History =
+                                        bcs::from_bytes(event.contents.as_ref()).unwrap();
+                                    applied_records += history.0.len();
+                                    tx.send(Message::PlayEffects(history)).unwrap();
                                 });
                             }
                         }
@@ -408,6 +692,18 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                                 .id
                                 .into();
 
+                            // Remember who played the game to show them in its replay.
+                            let active_game = state_arc.lock().unwrap().active_game.clone();
+                            let game = game_client.get_game(game_id).await.ok().or(active_game);
+
+                            if let Some(game) = game {
+                                let recruits = state_arc.lock().unwrap().game_recruits.clone();
+                                store_roster(
+                                    game.history.clone(),
+                                    Roster::from_game(&game, recruits),
+                                );
+                            }
+
                             let (effects, _events) = tx_runner
                                 .as_mut()
                                 .unwrap()
@@ -418,6 +714,7 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                             println!("Result: {:?}", effects.status);
 
                             state_arc.lock().unwrap().active_game = None;
+                            watched_game = None;
                             STORAGE.lock().unwrap().remove("active_game");
                             tx.send(Message::GameDestroyed).unwrap();
                         }
@@ -470,6 +767,42 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                             panic!("No address found");
                         }
                     }
+                    AppMessage::FetchRoster(replay) => {
+                        let roster = find_roster(&replay.history);
+
+                        tx.send(Message::ReplayRoster(roster)).unwrap();
+                    }
+                    AppMessage::ExportReplay(replay) => {
+                        if state_arc.lock().unwrap().presets.is_empty() {
+                            let presets = game_client.list_presets().await;
+                            state_arc.lock().unwrap().presets = presets;
+                        }
+
+                        let Some(preset) = state_arc
+                            .lock()
+                            .unwrap()
+                            .presets
+                            .iter()
+                            .find(|p| p.data.id == replay.preset_id)
+                            .map(|p| p.data.clone())
+                        else {
+                            eprintln!("Preset {} not found", replay.preset_id);
+                            continue;
+                        };
+
+                        let roster = find_roster(&replay.history);
+
+                        match ReplayFile::new(replay, preset, roster)
+                            .save(std::path::Path::new(REPLAYS_DIR))
+                        {
+                            Ok(paths) => {
+                                for path in paths {
+                                    println!("Replay exported to {}", path.display());
+                                }
+                            }
+                            Err(err) => eprintln!("Failed to export replay: {}", err),
+                        }
+                    }
                     AppMessage::FetchReplays => {
                         #[cfg(feature = "cache")]
                         if let Some(state) = STORAGE.lock().unwrap().get("replays") {
@@ -498,10 +831,131 @@ fn tokio_runtime(tx: Sender<Message>, rx_app: Receiver<AppMessage>, state_arc: A
                     }
                 }
             }
+
+            if let Some(game_id) = watched_game
+                && last_poll.elapsed() >= POLL_INTERVAL
+            {
+                last_poll = Instant::now();
+
+                let game = match game_client.get_game(game_id).await {
+                    Ok(game) => game,
+                    Err(err) => {
+                        eprintln!("Error: {}", err);
+                        continue;
+                    }
+                };
+
+                let players_changed = state_arc
+                    .lock()
+                    .unwrap()
+                    .active_game
+                    .as_ref()
+                    .is_some_and(|active| active.players.len() != game.players.len());
+
+                if players_changed {
+                    // Another player joined, restart the game with their
+                    // recruits placed on the map.
+                    println!("Player joined the game");
+                    let recruits = game_client.list_game_recruits(&game).await;
+                    let mut state = state_arc.lock().unwrap();
+                    applied_records = game.history.0.len();
+                    state.game_recruits = recruits;
+                    state.active_game = Some(game);
+                    tx.send(Message::GameStarted).unwrap();
+                } else if game.history.0.len() > applied_records {
+                    let records = game.history.0[applied_records..].to_vec();
+                    applied_records = game.history.0.len();
+                    state_arc.lock().unwrap().active_game = Some(game);
+                    tx.send(Message::PlayEffects(History(records))).unwrap();
+                }
+            }
         }
     });
 }
 
+/// Fetch the owned recruits, weapons and armor into the state and cache the
+/// recruits in the storage.
+async fn refresh_inventory(
+    game_client: &mut GameClient,
+    address: Address,
+    state_arc: &Arc<Mutex<State>>,
+) {
+    let recruits = game_client.list_recruits(address).await;
+    let weapons = game_client.list_weapons(address).await;
+    let armor = game_client.list_armor(address).await;
+    STORAGE
+        .lock()
+        .unwrap()
+        .set("recruits", &serde_json::to_string(&recruits).unwrap());
+
+    let mut state = state_arc.lock().unwrap();
+    state.recruits = recruits;
+    state.weapons = weapons;
+    state.armor = armor;
+}
+
+/// Read the stored rosters of the finished games.
+fn load_rosters() -> Vec<(History, Roster)> {
+    STORAGE
+        .lock()
+        .unwrap()
+        .get(ROSTERS_KEY)
+        .and_then(|rosters| serde_json::from_str(rosters.as_ref()).ok())
+        .unwrap_or_default()
+}
+
+/// Store the roster of a finished game, only the most recent ones are kept.
+fn store_roster(history: History, roster: Roster) {
+    let mut rosters = load_rosters();
+    rosters.retain(|(h, _)| *h != history);
+    rosters.push((history, roster));
+
+    if rosters.len() > MAX_ROSTERS {
+        rosters.drain(..rosters.len() - MAX_ROSTERS);
+    }
+
+    STORAGE
+        .lock()
+        .unwrap()
+        .set(ROSTERS_KEY, &serde_json::to_string(&rosters).unwrap());
+}
+
+/// Find the stored roster of a finished game, only games quit by this client
+/// have one. Recruits killed in the game are deleted on chain and can't be
+/// fetched later, so the units of other games are shown as unknown.
+fn find_roster(history: &History) -> Roster {
+    load_rosters()
+        .into_iter()
+        .find(|(h, _)| h == history)
+        .map(|(_, roster)| roster)
+        .unwrap_or_default()
+}
+
+/// Owned recruits assigned to each of the `spawns`, the spawns without one or
+/// with a recruit which is no longer owned get new recruits.
+fn resolve_squad(
+    recruits: &[WithRef<Recruit>],
+    assigned: &[Option<ID>],
+    spawns: usize,
+) -> Vec<Option<WithRef<Recruit>>> {
+    (0..spawns)
+        .map(|i| {
+            let id = assigned.get(i).copied().flatten()?;
+            recruits.iter().find(|r| r.data.id == id).cloned()
+        })
+        .collect()
+}
+
+/// Placed recruits are kept in the game until it ends.
+fn remove_placed(recruits: &mut Vec<WithRef<Recruit>>, squad: &[Option<WithRef<Recruit>>]) {
+    let placed = squad
+        .iter()
+        .flatten()
+        .map(|r| r.data.id)
+        .collect::<Vec<_>>();
+    recruits.retain(|r| !placed.contains(&r.data.id));
+}
+
 /// Handles the login process using zklogin.
 async fn login_action(
     public_key: &Ed25519PublicKey,
@@ -522,44 +976,3 @@ async fn login_action(
 
     Ok((address, zkp, max_epoch))
 }
-
-// === Utils ===
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WithRef<T> {
-    pub object_ref: ObjectReference,
-    pub data: T,
-}
-
-impl<'de, T> WithRef<T> {
-    pub fn from_rpc_object(obj: &'de Object) -> Result<Self, anyhow::Error>
-    where
-        T: Deserialize<'de>,
-    {
-        Ok(WithRef {
-            data: bcs::from_bytes(
-                obj.contents
-                    .as_ref()
-                    .ok_or(anyhow::anyhow!("No contents"))?
-                    .value(),
-            )?,
-            object_ref: ObjectReference::new(
-                Address::from_str(&obj.object_id()).unwrap(),
-                Version::from(obj.version()),
-                Digest::from_base58(&obj.digest()).unwrap(),
-            ),
-        })
-    }
-}
-
-impl Default for State {
-    fn default() -> Self {
-        Self {
-            address: None,
-            presets: vec![],
-            recruits: vec![],
-            replays: vec![],
-            active_game: None,
-        }
-    }
-}
@@ -0,0 +1,534 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Local implementation of the game rules defined in the Move `map` and `unit`
+//! modules. Every action mutates the `GameMap` the same way the contract does
+//! and returns the `Record`s the contract would emit, which makes it possible
+//! to play-test maps without sending transactions.
+//!
+//! Randomness is provided by a seedable `Rng`, which is called in the same
+//! order as the `RandomGenerator` in Move. Outcomes follow the same rules and
+//! distribution, but not the same values as on chain.
+
+use std::{cell::RefCell, rc::Rc};
+
+use ::rand::{Rng as _, SeedableRng, rngs::StdRng};
+use thiserror::Error;
+
+use crate::types::{
+    Direction, GRENADE_DAMAGE, GRENADE_RADIUS, GRENADE_RANGE, GameMap, Record, TileType, Unit,
+    can_step, hit_chance, manhattan_distance,
+};
+
+/// Errors match the abort codes of the `map` and `unit` modules.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RulesError {
+    #[error("Path is too short")]
+    PathTooShort,
+
+    #[error("Path is unwalkable")]
+    PathUnwalkable,
+
+    #[error("Tile is out of bounds")]
+    TileOutOfBounds,
+
+    #[error("No unit on the tile")]
+    NoUnit,
+
+    #[error("No AP left")]
+    NoAP,
+
+    #[error("Out of ammo")]
+    OutOfAmmo,
+
+    #[error("Ammo is full")]
+    FullAmmo,
+
+    #[error("Target is out of range")]
+    RangeExceeded,
+}
+
+/// Seedable replacement for the `RandomGenerator` in Move.
+pub struct Rng(StdRng);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+
+    /// Generate a `u8` in the range, both ends inclusive.
+    pub fn generate_u8_in_range(&mut self, min: u8, max: u8) -> u8 {
+        self.0.gen_range(min..=max)
+    }
+
+    pub fn generate_bool(&mut self) -> bool {
+        self.0.gen_bool(0.5)
+    }
+}
+
+/// Proceed to the next turn. Units' AP is reset lazily on their next action.
+pub fn next_turn(map: &mut GameMap) -> Record {
+    map.turn += 1;
+    Record::NextTurn(map.turn)
+}
+
+/// Move a unit along the path. The first two values are the coordinates of the
+/// unit, the rest are directions. Costs 1 AP.
+pub fn move_unit(map: &mut GameMap, path: &[u8]) -> Result<Record, RulesError> {
+    if path.len() <= 2 {
+        return Err(RulesError::PathTooShort);
+    }
+
+    let distance = (path.len() - 2) as u8;
+    let (x0, y0) = (path[0], path[1]);
+
+    if x0 >= map.rows() || y0 >= map.cols() {
+        return Err(RulesError::TileOutOfBounds);
+    }
+
+    let (x1, y1) = check_path(map, path).ok_or(RulesError::PathUnwalkable)?;
+    let unit_rc = unit_at(map, (x0, y0))?;
+    let mut unit = *unit_rc.borrow();
+
+    try_reset_ap(&mut unit, map.turn);
+
+    if unit.stats.mobility() < distance as i8 {
+        return Err(RulesError::RangeExceeded);
+    }
+
+    if unit.ap.value() == 0 {
+        return Err(RulesError::NoAP);
+    }
+
+    unit.ap.decrease(1);
+    *unit_rc.borrow_mut() = unit;
+
+    let unit_rc = map.grid[x0 as usize][y0 as usize].unit.take().unwrap();
+    map.grid[x1 as usize][y1 as usize].unit.replace(unit_rc);
+
+    Ok(Record::Move(path.to_vec()))
+}
+
+/// Check that the path is walkable and return its last cell. Units, high cover
+/// and unwalkable tiles block the path, same as `map::check_path`.
+pub fn check_path(map: &GameMap, path: &[u8]) -> Option<(u8, u8)> {
+    let (mut x, mut y) = (*path.first()?, *path.get(1)?);
+
+    for direction in path.iter().skip(2) {
+        let from = (x, y);
+
+        (x, y) = match Direction::try_from(*direction).ok()? {
+            Direction::Up => (x.checked_sub(1)?, y),
+            Direction::Down => (x + 1, y),
+            Direction::Left => (x, y.checked_sub(1)?),
+            Direction::Right => (x, y + 1),
+            Direction::None => return None,
+        };
+
+        if !can_step(map, from, (x, y)) {
+            return None;
+        }
+    }
+
+    Some((x, y))
+}
+
+/// Reload the unit's weapon. Costs 1 AP.
+pub fn perform_reload(map: &mut GameMap, position: (u8, u8)) -> Result<Record, RulesError> {
+    let unit_rc = unit_at(map, position)?;
+    let mut unit = *unit_rc.borrow();
+
+    try_reset_ap(&mut unit, map.turn);
+
+    if unit.ap.value() == 0 {
+        return Err(RulesError::NoAP);
+    }
+
+    if unit.ammo.is_full() {
+        return Err(RulesError::FullAmmo);
+    }
+
+    unit.ap.decrease(1);
+    unit.ammo.reset();
+    *unit_rc.borrow_mut() = unit;
+
+    Ok(Record::Reload(vec![position.0 as u16, position.1 as u16]))
+}
+
+/// Perform a ranged attack from `origin` at `target`. Depletes the AP of the
+/// attacker and uses 1 ammo.
+pub fn perform_attack(
+    map: &mut GameMap,
+    rng: &mut Rng,
+    origin: (u8, u8),
+    target: (u8, u8),
+) -> Result<Vec<Record>, RulesError> {
+    let mut history = vec![Record::Attack {
+        origin: vec![origin.0 as u16, origin.1 as u16],
+        target: vec![target.0 as u16, target.1 as u16],
+    }];
+
+    let range = manhattan_distance(origin, target);
+    let defense = if range > 0 {
+        map.cover_bonus(origin, target)
+    } else {
+        0
+    };
+
+    let attacker_rc = unit_at(map, origin)?;
+    let mut attacker = *attacker_rc.borrow();
+
+    try_reset_ap(&mut attacker, map.turn);
+
+    let (is_hit, is_crit, damage) = unit_attack(&mut attacker, rng, range, defense)?;
+    let target_rc = unit_at(map, target)?;
+
+    *attacker_rc.borrow_mut() = attacker;
+
+    let mut unit = *target_rc.borrow();
+    let (is_dodged, damage, is_kia) = if is_hit && damage > 0 {
+        apply_damage(&mut unit, rng, damage, true)
+    } else {
+        (false, 0, false)
+    };
+
+    if is_dodged {
+        history.push(Record::Dodged);
+    }
+
+    if is_hit {
+        history.push(match is_crit {
+            true => Record::CriticalHit(damage),
+            false => Record::Damage(damage),
+        });
+    } else {
+        history.push(Record::Miss);
+    }
+
+    *target_rc.borrow_mut() = unit;
+
+    if is_kia {
+        history.push(Record::UnitKIA(unit.recruit));
+        remove_unit(map, target);
+    }
+
+    Ok(history)
+}
+
+/// Throw a grenade from `origin` at `target`. Costs 1 AP, deals damage to all
+/// units in the area (no dodge) and destroys covers.
+pub fn perform_grenade(
+    map: &mut GameMap,
+    rng: &mut Rng,
+    origin: (u8, u8),
+    target: (u8, u8),
+) -> Result<Vec<Record>, RulesError> {
+    if manhattan_distance(origin, target) > GRENADE_RANGE {
+        return Err(RulesError::RangeExceeded);
+    }
+
+    let unit_rc = unit_at(map, origin)?;
+    let mut unit = *unit_rc.borrow();
+
+    try_reset_ap(&mut unit, map.turn);
+
+    if unit.ap.value() == 0 {
+        return Err(RulesError::NoAP);
+    }
+
+    if unit.grenade_used {
+        return Err(RulesError::OutOfAmmo);
+    }
+
+    unit.ap.decrease(1);
+    unit.grenade_used = true;
+    *unit_rc.borrow_mut() = unit;
+
+    let mut history = vec![Record::Grenade(
+        target.0 as u16,
+        target.1 as u16,
+        GRENADE_RADIUS as u16,
+    )];
+
+    for (x, y) in map.grenade_area(target, GRENADE_RADIUS) {
+        if let Some(unit_rc) = map.grid[x as usize][y as usize].unit.clone() {
+            let mut unit = *unit_rc.borrow();
+            let (_, damage, is_kia) = apply_damage(&mut unit, rng, GRENADE_DAMAGE, false);
+
+            history.push(Record::Damage(damage));
+            *unit_rc.borrow_mut() = unit;
+
+            if is_kia {
+                history.push(Record::UnitKIA(unit.recruit));
+                remove_unit(map, (x, y));
+            }
+        }
+
+        let tile = &mut map.grid[x as usize][y as usize];
+        if matches!(tile.tile_type, TileType::Cover { .. }) {
+            tile.tile_type = TileType::Empty;
+        }
+    }
+
+    Ok(history)
+}
+
+/// Reset the AP of the unit if it hasn't acted in the current turn.
+fn try_reset_ap(unit: &mut Unit, turn: u16) {
+    if unit.last_turn < turn {
+        unit.ap.reset();
+    }
+
+    unit.last_turn = turn;
+}
+
+/// Port of `unit::perform_attack`. Returns (is_hit, is_critical, damage).
+fn unit_attack(
+    unit: &mut Unit,
+    rng: &mut Rng,
+    range: u8,
+    defense: u8,
+) -> Result<(bool, bool, u8), RulesError> {
+    if unit.ap.value() == 0 {
+        return Err(RulesError::NoAP);
+    }
+
+    if unit.ammo.value() == 0 {
+        return Err(RulesError::OutOfAmmo);
+    }
+
+    unit.ap.deplete();
+    unit.ammo.decrease(1);
+
+    let chance = hit_chance(unit, range, defense).ok_or(RulesError::RangeExceeded)?;
+    let is_hit = rng.generate_u8_in_range(0, 99) < chance;
+
+    if !is_hit {
+        return Ok((false, false, 0));
+    }
+
+    let damage_stat = stat(unit.stats.damage());
+    let spread = stat(unit.stats.spread());
+    let is_critical = rng.generate_u8_in_range(0, 99) < stat(unit.stats.crit_chance());
+    let damage = match rng.generate_bool() {
+        true => damage_stat + rng.generate_u8_in_range(0, spread),
+        false => damage_stat.saturating_sub(rng.generate_u8_in_range(0, spread)),
+    };
+
+    let is_plus_one = rng.generate_u8_in_range(0, 99) < stat(unit.stats.plus_one());
+    let damage = if is_plus_one { damage + 1 } else { damage };
+    let damage = if is_critical {
+        damage + damage_stat / 2
+    } else {
+        damage
+    };
+
+    Ok((true, is_critical, damage))
+}
+
+/// Port of `unit::apply_damage`. Returns (is_dodged, damage, is_kia).
+fn apply_damage(unit: &mut Unit, rng: &mut Rng, damage: u8, can_dodge: bool) -> (bool, u8, bool) {
+    let armor = stat(unit.stats.armor());
+    let damage = if armor >= damage { 1 } else { damage - armor };
+
+    // the wheel is spun even if the attack cannot be dodged
+    let roll = rng.generate_u8_in_range(0, 99);
+    if can_dodge && roll < stat(unit.stats.dodge()) {
+        return (true, 0, false);
+    }
+
+    unit.hp.decrease(damage as u16);
+    (false, damage, unit.hp.value() == 0)
+}
+
+fn unit_at(map: &GameMap, (x, y): (u8, u8)) -> Result<Rc<RefCell<Unit>>, RulesError> {
+    map.grid
+        .get(x as usize)
+        .and_then(|row| row.get(y as usize))
+        .ok_or(RulesError::TileOutOfBounds)?
+        .unit
+        .clone()
+        .ok_or(RulesError::NoUnit)
+}
+
+fn remove_unit(map: &mut GameMap, (x, y): (u8, u8)) {
+    if let Some(unit) = map.grid[x as usize][y as usize].unit.take() {
+        map.units.retain(|u| !Rc::ptr_eq(u, &unit));
+    }
+}
+
+/// Stats are signed in Rust, negative values are treated as 0.
+fn stat(value: i8) -> u8 {
+    value.max(0) as u8
+}
+
+#[cfg(test)]
+fn test_map() -> GameMap {
+    use crate::types::{ID, Stats};
+    use sui_sdk_types::Address;
+
+    let mut map = GameMap::new(5, 5);
+    let stats = Stats::default().add(&Stats::default_weapon());
+
+    for (i, (x, y)) in [(0, 0), (0, 4)].into_iter().enumerate() {
+        let id = ID(Address::from_hex(format!("0x{}", i + 1)).unwrap());
+        let unit = Rc::new(RefCell::new(Unit::from_stats(id, stats)));
+        map.grid[x][y].unit = Some(unit.clone());
+        map.units.push(unit);
+    }
+
+    map
+}
+
+#[test]
+fn test_move_unit() {
+    use crate::types::HIGH_COVER;
+
+    let mut map = test_map();
+    let (down, right) = (Direction::Down.into(), Direction::Right.into());
+
+    // low cover does not block movement, high cover does
+    map.grid[1][0].tile_type = TileType::Cover {
+        left: 0,
+        top: 1,
+        right: 0,
+        bottom: 0,
+    };
+
+    assert!(move_unit(&mut map, &[0, 0, down]).is_ok());
+    assert_eq!(map.grid[1][0].unit.as_ref().unwrap().borrow().ap.value(), 1);
+
+    map.grid[1][1].tile_type = TileType::Cover {
+        left: HIGH_COVER,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+
+    assert_eq!(
+        move_unit(&mut map, &[1, 0, right]),
+        Err(RulesError::PathUnwalkable)
+    );
+    assert_eq!(
+        move_unit(
+            &mut map,
+            &[1, 0, down, right, right, right, right, right, right, right]
+        ),
+        Err(RulesError::PathUnwalkable) // off the grid
+    );
+    assert_eq!(move_unit(&mut map, &[1, 0]), Err(RulesError::PathTooShort));
+
+    // second move uses the last AP
+    assert!(move_unit(&mut map, &[1, 0, down]).is_ok());
+    assert_eq!(move_unit(&mut map, &[2, 0, down]), Err(RulesError::NoAP));
+
+    // AP is reset in the next turn
+    next_turn(&mut map);
+    assert!(move_unit(&mut map, &[2, 0, down]).is_ok());
+}
+
+#[test]
+fn test_perform_attack() {
+    let mut map = test_map();
+    let mut rng = Rng::new(42);
+
+    let history = perform_attack(&mut map, &mut rng, (0, 0), (0, 4)).unwrap();
+
+    assert!(matches!(history[0], Record::Attack { .. }));
+    assert!(matches!(
+        history[1],
+        Record::Damage(_) | Record::CriticalHit(_) | Record::Miss
+    ));
+
+    // attack depletes the AP and uses ammo
+    let attacker = map.grid[0][0].unit.clone().unwrap();
+    assert_eq!(attacker.borrow().ap.value(), 0);
+    assert_eq!(attacker.borrow().ammo.value(), 2);
+    assert_eq!(
+        perform_attack(&mut map, &mut rng, (0, 0), (0, 4)),
+        Err(RulesError::NoAP)
+    );
+
+    // same seed - same outcome
+    let mut map = test_map();
+    let mut rng = Rng::new(42);
+    assert_eq!(
+        perform_attack(&mut map, &mut rng, (0, 0), (0, 4)).unwrap(),
+        history
+    );
+}
+
+#[test]
+fn test_perform_reload() {
+    let mut map = test_map();
+
+    assert_eq!(perform_reload(&mut map, (0, 0)), Err(RulesError::FullAmmo));
+    assert_eq!(perform_reload(&mut map, (1, 1)), Err(RulesError::NoUnit));
+
+    map.grid[0][0]
+        .unit
+        .as_ref()
+        .unwrap()
+        .borrow_mut()
+        .ammo
+        .decrease(1);
+    assert!(perform_reload(&mut map, (0, 0)).is_ok());
+
+    let unit = map.grid[0][0].unit.clone().unwrap();
+    assert!(unit.borrow().ammo.is_full());
+    assert_eq!(unit.borrow().ap.value(), 1);
+}
+
+#[test]
+fn test_perform_grenade() {
+    let mut map = test_map();
+    let mut rng = Rng::new(0);
+    let victim = map.grid[0][4].unit.clone().unwrap().borrow().recruit;
+
+    map.grid[0][3].tile_type = TileType::Cover {
+        left: 1,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+
+    // damage is 4, unit has 10 HP
+    for _ in 0..2 {
+        let history = perform_grenade(&mut map, &mut rng, (0, 0), (0, 3));
+        assert_eq!(
+            history,
+            Ok(vec![Record::Grenade(0, 3, 2), Record::Damage(4)])
+        );
+
+        // grenade can only be used once per unit
+        assert_eq!(
+            perform_grenade(&mut map, &mut rng, (0, 0), (0, 3)),
+            Err(RulesError::OutOfAmmo)
+        );
+
+        map.grid[0][0]
+            .unit
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .grenade_used = false;
+        next_turn(&mut map);
+    }
+
+    assert!(matches!(map.grid[0][3].tile_type, TileType::Empty));
+    assert_eq!(
+        perform_grenade(&mut map, &mut rng, (0, 0), (0, 3)),
+        Ok(vec![
+            Record::Grenade(0, 3, 2),
+            Record::Damage(4),
+            Record::UnitKIA(victim)
+        ])
+    );
+    assert!(map.grid[0][4].unit.is_none());
+    assert_eq!(map.units.len(), 1);
+
+    assert_eq!(
+        perform_grenade(&mut map, &mut rng, (0, 0), (4, 4)),
+        Err(RulesError::RangeExceeded)
+    );
+}
@@ -88,6 +88,12 @@ impl SoundStore {
     }
 }
 
+impl Default for SoundStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Asset for Effect {
     type AssetType = Sound;
 
@@ -11,8 +11,11 @@ use sui_sdk_types::Address;
 
 use crate::{
     WithRef,
-    config::{COMMANDER_OBJ, PRESET_STRUCT_TAG, RECRUIT_STRUCT_TAG, REPLAY_STRUCT_TAG},
-    types::{Game, Preset, Recruit, Replay},
+    config::{
+        ARMOR_STRUCT_TAG, COMMANDER_OBJ, HOST_STRUCT_TAG, PRESET_STRUCT_TAG, RECRUIT_STRUCT_TAG,
+        REPLAY_STRUCT_TAG, WEAPON_STRUCT_TAG,
+    },
+    types::{Armor, Game, Host, ID, Preset, Recruit, Replay, Weapon},
 };
 
 pub struct GameClient {
@@ -57,6 +60,54 @@ impl GameClient {
             .map_err(|e| anyhow::anyhow!("Failed to deserialize game: {}", e))
     }
 
+    pub async fn get_recruit(&mut self, recruit_id: Address) -> Result<Recruit, anyhow::Error> {
+        self.client
+            .ledger_client()
+            .get_object(
+                GetObjectRequest::default()
+                    .with_object_id(recruit_id.to_string())
+                    .with_read_mask(FieldMask {
+                        paths: vec!["contents".to_string(), "digest".to_string()],
+                    }),
+            )
+            .await?
+            .into_inner()
+            .object
+            .ok_or(anyhow::anyhow!("Recruit not found"))?
+            .contents
+            .ok_or(anyhow::anyhow!("BCS is not present in object"))?
+            .deserialize::<Recruit>()
+            .map_err(|e| anyhow::anyhow!("Failed to deserialize recruit: {}", e))
+    }
+
+    /// Fetch the recruits placed on the map of the `Game`. While the game is
+    /// running, recruits are stored in the game and can be fetched by their ID.
+    pub async fn list_game_recruits(&mut self, game: &Game) -> Vec<Recruit> {
+        let ids = game
+            .map
+            .grid
+            .iter()
+            .flatten()
+            .filter_map(|tile| tile.unit.map(|unit| unit.recruit))
+            .collect::<Vec<ID>>();
+
+        self.get_recruits(&ids).await
+    }
+
+    /// Fetch the recruits by their IDs, the ones which can't be fetched are
+    /// skipped.
+    pub async fn get_recruits(&mut self, ids: &[ID]) -> Vec<Recruit> {
+        let mut recruits = Vec::new();
+        for id in ids {
+            match self.get_recruit((*id).into()).await {
+                Ok(recruit) => recruits.push(recruit),
+                Err(err) => eprintln!("Failed to fetch recruit {}: {}", id, err),
+            }
+        }
+
+        recruits
+    }
+
     pub async fn list_presets(&mut self) -> Vec<WithRef<Preset>> {
         self.client
             .list_owned_objects(
@@ -72,6 +123,22 @@ impl GameClient {
             .await
     }
 
+    pub async fn list_hosted_games(&mut self) -> Vec<WithRef<Host>> {
+        self.client
+            .list_owned_objects(
+                ListOwnedObjectsRequest::default()
+                    .with_owner(COMMANDER_OBJ.to_string())
+                    .with_object_type(HOST_STRUCT_TAG.to_string())
+                    .with_page_size(100)
+                    .with_read_mask(FieldMask {
+                        paths: vec!["contents".to_string(), "digest".to_string()],
+                    }),
+            )
+            .map(|obj| WithRef::from_rpc_object(&obj.unwrap()).unwrap())
+            .collect::<Vec<WithRef<Host>>>()
+            .await
+    }
+
     pub async fn list_recruits(&mut self, address: Address) -> Vec<WithRef<Recruit>> {
         self.client
             .list_owned_objects(
@@ -88,6 +155,38 @@ impl GameClient {
             .await
     }
 
+    pub async fn list_weapons(&mut self, address: Address) -> Vec<WithRef<Weapon>> {
+        self.client
+            .list_owned_objects(
+                ListOwnedObjectsRequest::default()
+                    .with_owner(address.to_string())
+                    .with_object_type(WEAPON_STRUCT_TAG.to_string())
+                    .with_page_size(100)
+                    .with_read_mask(FieldMask {
+                        paths: vec!["contents".to_string(), "digest".to_string()],
+                    }),
+            )
+            .map(|obj| WithRef::from_rpc_object(&obj.unwrap()).unwrap())
+            .collect::<Vec<WithRef<Weapon>>>()
+            .await
+    }
+
+    pub async fn list_armor(&mut self, address: Address) -> Vec<WithRef<Armor>> {
+        self.client
+            .list_owned_objects(
+                ListOwnedObjectsRequest::default()
+                    .with_owner(address.to_string())
+                    .with_object_type(ARMOR_STRUCT_TAG.to_string())
+                    .with_page_size(100)
+                    .with_read_mask(FieldMask {
+                        paths: vec!["contents".to_string(), "digest".to_string()],
+                    }),
+            )
+            .map(|obj| WithRef::from_rpc_object(&obj.unwrap()).unwrap())
+            .collect::<Vec<WithRef<Armor>>>()
+            .await
+    }
+
     pub async fn list_replays(&mut self, address: Address) -> Vec<WithRef<Replay>> {
         self.client
             .list_owned_objects(
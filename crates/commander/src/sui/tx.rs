@@ -15,16 +15,16 @@ use sui_rpc::{
     proto::sui::rpc::v2::{ExecuteTransactionRequest, GetObjectRequest, ListOwnedObjectsRequest},
 };
 use sui_sdk_types::{
-    Address, Digest, IdOperation, Identifier, ObjectOut, ObjectReference, Owner, Transaction,
-    TransactionEffects, TransactionEffectsV2, TransactionEvents, UserSignature, Version,
-    ZkLoginAuthenticator, ZkLoginInputs,
+    Address, Argument, Digest, IdOperation, Identifier, ObjectOut, ObjectReference, Owner,
+    Transaction, TransactionEffects, TransactionEffectsV2, TransactionEvents, TypeTag,
+    UserSignature, Version, ZkLoginAuthenticator, ZkLoginInputs,
 };
 use sui_transaction_builder::{Function, Serialized, TransactionBuilder, unresolved::Input};
 
 use crate::{
     WithRef,
-    config::{COMMANDER_OBJ, COMMANDER_PKG, SUI_COIN_TYPE},
-    types::{GridPath, Preset},
+    config::{ARMOR_STRUCT_TAG, COMMANDER_OBJ, COMMANDER_PKG, SUI_COIN_TYPE, WEAPON_STRUCT_TAG},
+    types::{GridPath, Host, ID, Map, Preset, Recruit, Slot, UpgradeKind},
 };
 
 pub struct TxExecutor {
@@ -57,6 +57,16 @@ pub struct Coin {
     balance: u64,
 }
 
+/// Shorthand for a function in the `recruit` module.
+fn recruit_function(name: &str) -> Result<Function, anyhow::Error> {
+    Ok(Function::new(
+        Address::from_hex(COMMANDER_PKG)?,
+        Identifier::new("recruit")?,
+        Identifier::new(name)?,
+        vec![],
+    ))
+}
+
 impl From<WithRef<Coin>> for Input {
     fn from(coin: WithRef<Coin>) -> Self {
         Input::owned(
@@ -89,18 +99,24 @@ impl TxExecutor {
         }
     }
 
-    pub async fn start_game(&mut self, preset: WithRef<Preset>) -> Result<Address, anyhow::Error> {
+    /// Start a new single player game from the `Preset`. The `squad` has a
+    /// recruit for every spawn, see `place_squad`.
+    pub async fn start_game(
+        &mut self,
+        preset: WithRef<Preset>,
+        squad: Vec<Option<WithRef<Recruit>>>,
+    ) -> Result<Address, anyhow::Error> {
         let rgp = self.rgp.unwrap_or(1000);
         let gas_coins = self.get_gas_coins().await?;
         let preset_ref = self
             .get_owned_object_ref(*preset.object_ref.object_id())
             .await?;
+        let squad = self.squad_refs(&squad).await?;
 
         let commander = self
             .get_shared_object_ref(Address::from_str(COMMANDER_OBJ)?, true)
             .await?;
 
-        let recruits_num = preset.data.positions.len();
         let mut ptb = TransactionBuilder::new();
         let commander = ptb.input(commander);
         let preset = ptb.input(Input::receiving(
@@ -119,30 +135,69 @@ impl TxExecutor {
             vec![commander, preset],
         );
 
-        for _ in 0..recruits_num {
-            let name = ptb.input(Serialized(&"Recruit".to_string()));
-            let backstory = ptb.input(Serialized(&"Backstory".to_string()));
+        Self::place_squad(&mut ptb, game, &squad)?;
 
-            let recruit = ptb.move_call(
-                Function::new(
-                    Address::from_hex(COMMANDER_PKG)?,
-                    Identifier::new("recruit")?,
-                    Identifier::new("new")?,
-                    vec![],
-                ),
-                vec![name, backstory],
-            );
+        ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("commander")?,
+                Identifier::new("share")?,
+                vec![],
+            ),
+            vec![game],
+        );
 
-            ptb.move_call(
-                Function::new(
-                    Address::from_hex(COMMANDER_PKG)?,
-                    Identifier::new("commander")?,
-                    Identifier::new("place_recruit")?,
-                    vec![],
-                ),
-                vec![game, recruit],
-            );
-        }
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        let (effects, _events) = self.execute_tx(ptb.finish()?).await?;
+
+        Self::created_game_id(&effects)
+    }
+
+    /// Host a new multiplayer game from the `Preset`. The host places the
+    /// `squad` on the first spawns, the rest are filled by the player who
+    /// joins the game.
+    pub async fn host_game(
+        &mut self,
+        preset: WithRef<Preset>,
+        squad: Vec<Option<WithRef<Recruit>>>,
+    ) -> Result<Address, anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let preset_ref = self
+            .get_owned_object_ref(*preset.object_ref.object_id())
+            .await?;
+        let squad = self.squad_refs(&squad).await?;
+
+        let commander = self
+            .get_shared_object_ref(Address::from_str(COMMANDER_OBJ)?, true)
+            .await?;
+        let clock = self.clock().await?;
+
+        let mut ptb = TransactionBuilder::new();
+        let commander = ptb.input(commander);
+        let clock = ptb.input(clock);
+        let preset = ptb.input(Input::receiving(
+            *preset_ref.object_id(),
+            preset_ref.version(),
+            *preset_ref.digest(),
+        ));
+
+        let game = ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("commander")?,
+                Identifier::new("host_game")?,
+                vec![],
+            ),
+            vec![commander, clock, preset],
+        );
+
+        Self::place_squad(&mut ptb, game, &squad)?;
 
         ptb.move_call(
             Function::new(
@@ -162,49 +217,436 @@ impl TxExecutor {
 
         let (effects, _events) = self.execute_tx(ptb.finish()?).await?;
 
-        // The only new shared object in this transaction is the game object.
-        // Hence, the search for it is straightforward.
-        let game_id = effects
-            .changed_objects
-            .iter()
-            .find(|obj| {
-                matches!(
-                    obj.output_state,
-                    ObjectOut::ObjectWrite {
-                        digest: _,
-                        owner: Owner::Shared(_)
-                    }
-                ) && matches!(obj.id_operation, IdOperation::Created)
-            })
-            .ok_or(anyhow::anyhow!("Game not found in effects"))?
-            .object_id;
+        Self::created_game_id(&effects)
+    }
 
-        Ok(game_id)
+    /// Join a game hosted by another player, consuming the `Host` object and
+    /// placing the `squad` on the spawns left by the host.
+    pub async fn join_game(
+        &mut self,
+        host: WithRef<Host>,
+        squad: Vec<Option<WithRef<Recruit>>>,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let squad = self.squad_refs(&squad).await?;
+
+        let commander = self
+            .get_shared_object_ref(Address::from_str(COMMANDER_OBJ)?, true)
+            .await?;
+        let game = self
+            .get_shared_object_ref(host.data.game_id.into(), true)
+            .await?;
+
+        let mut ptb = TransactionBuilder::new();
+        let commander = ptb.input(commander);
+        let game = ptb.input(game);
+        let host = ptb.input(host);
+
+        ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("commander")?,
+                Identifier::new("join_game")?,
+                vec![],
+            ),
+            vec![commander, game, host],
+        );
+
+        Self::place_squad(&mut ptb, game, &squad)?;
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
     }
 
-    pub async fn move_unit(
+    /// Publish a new `Preset` built from the `Map` and the spawn positions.
+    pub async fn publish_map(
         &mut self,
-        game_id: Address,
-        path: GridPath,
+        name: String,
+        map: &Map,
+        positions: &[Vec<u8>],
     ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
         let rgp = self.rgp.unwrap_or(1000);
         let gas_coins = self.get_gas_coins().await?;
         let mut ptb = TransactionBuilder::new();
 
-        let game = self.get_shared_object_ref(game_id, true).await?;
-        let game_arg = ptb.input(game);
-        let path_arg = ptb.input(Serialized(&path.to_direction_path()));
+        let commander = self
+            .get_shared_object_ref(Address::from_str(COMMANDER_OBJ)?, true)
+            .await?;
+
+        let bytes = map.to_publish_bytes(positions);
+
+        let commander_arg = ptb.input(commander);
+        let name_arg = ptb.input(Serialized(&name));
+        let bytes_arg = ptb.input(Serialized(&bytes));
 
         ptb.move_call(
             Function::new(
                 Address::from_hex(COMMANDER_PKG)?,
                 Identifier::new("commander")?,
-                Identifier::new("move_unit")?,
+                Identifier::new("publish_map")?,
+                vec![],
+            ),
+            vec![commander_arg, name_arg, bytes_arg],
+        );
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    /// Hire a new recruit and transfer it to the sender.
+    pub async fn hire_recruit(
+        &mut self,
+        name: String,
+        backstory: String,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let name_arg = ptb.input(Serialized(&name));
+        let backstory_arg = ptb.input(Serialized(&backstory));
+        let sender_arg = ptb.input(Serialized(&self.address));
+
+        let recruit = ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("recruit")?,
+                Identifier::new("new")?,
+                vec![],
+            ),
+            vec![name_arg, backstory_arg],
+        );
+
+        ptb.transfer_objects(vec![recruit], sender_arg);
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    /// Dismiss the recruit. Its weapon and armor, if any, are returned to the
+    /// sender.
+    pub async fn dismiss_recruit(
+        &mut self,
+        recruit: WithRef<Recruit>,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let recruit_ref = self
+            .get_owned_object_ref(*recruit.object_ref.object_id())
+            .await?;
+        let recruit_arg = ptb.input(Input::owned(
+            *recruit_ref.object_id(),
+            recruit_ref.version(),
+            *recruit_ref.digest(),
+        ));
+        let sender_arg = ptb.input(Serialized(&self.address));
+
+        let equipment = ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("recruit")?,
+                Identifier::new("dismiss")?,
+                vec![],
+            ),
+            vec![recruit_arg],
+        );
+
+        // `dismiss` returns `(Option<Weapon>, Option<Armor>)`, both have to be
+        // unpacked as neither has `drop`.
+        let slots = [
+            (recruit.data.weapon.is_some(), WEAPON_STRUCT_TAG),
+            (recruit.data.armor.is_some(), ARMOR_STRUCT_TAG),
+        ];
+
+        for (ix, (is_some, type_tag)) in slots.into_iter().enumerate() {
+            let item = equipment
+                .nested(ix as u16)
+                .ok_or(anyhow::anyhow!("Invalid dismiss result"))?;
+            let function = if is_some {
+                "destroy_some"
+            } else {
+                "destroy_none"
+            };
+            let result = ptb.move_call(
+                Function::new(
+                    Address::from_hex("0x1")?,
+                    Identifier::new("option")?,
+                    Identifier::new(function)?,
+                    vec![TypeTag::from_str(type_tag)?],
+                ),
+                vec![item],
+            );
+
+            if is_some {
+                ptb.transfer_objects(vec![result], sender_arg);
+            }
+        }
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    /// Equip an owned item to the `Slot` of the recruit. The item currently in
+    /// the slot, if any, is returned to the sender.
+    pub async fn equip_item(
+        &mut self,
+        recruit: WithRef<Recruit>,
+        slot: Slot,
+        item: ID,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let recruit_input = self.owned_input(*recruit.object_ref.object_id()).await?;
+        let item_input = self.owned_input(item.into()).await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let recruit_arg = ptb.input(recruit_input);
+        let item_arg = ptb.input(item_input);
+
+        if slot.is_equipped(&recruit.data) {
+            let sender_arg = ptb.input(Serialized(&self.address));
+            let removed =
+                ptb.move_call(recruit_function(slot.remove_function())?, vec![recruit_arg]);
+            ptb.transfer_objects(vec![removed], sender_arg);
+        }
+
+        ptb.move_call(
+            recruit_function(slot.add_function())?,
+            vec![recruit_arg, item_arg],
+        );
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    /// Remove the item from the `Slot` of the recruit and return it to the
+    /// sender.
+    pub async fn unequip_item(
+        &mut self,
+        recruit: WithRef<Recruit>,
+        slot: Slot,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let recruit_input = self.owned_input(*recruit.object_ref.object_id()).await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let recruit_arg = ptb.input(recruit_input);
+        let sender_arg = ptb.input(Serialized(&self.address));
+        let removed = ptb.move_call(recruit_function(slot.remove_function())?, vec![recruit_arg]);
+        ptb.transfer_objects(vec![removed], sender_arg);
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    /// Swap the items in the `Slot` of two recruits. Either of the slots can be
+    /// empty, in which case the item is simply moved.
+    pub async fn swap_items(
+        &mut self,
+        recruit: WithRef<Recruit>,
+        other: WithRef<Recruit>,
+        slot: Slot,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let recruit_input = self.owned_input(*recruit.object_ref.object_id()).await?;
+        let other_input = self.owned_input(*other.object_ref.object_id()).await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let recruit_arg = ptb.input(recruit_input);
+        let other_arg = ptb.input(other_input);
+
+        let own_item = match slot.is_equipped(&recruit.data) {
+            true => {
+                Some(ptb.move_call(recruit_function(slot.remove_function())?, vec![recruit_arg]))
+            }
+            false => None,
+        };
+
+        let other_item = match slot.is_equipped(&other.data) {
+            true => Some(ptb.move_call(recruit_function(slot.remove_function())?, vec![other_arg])),
+            false => None,
+        };
+
+        if let Some(item) = own_item {
+            ptb.move_call(
+                recruit_function(slot.add_function())?,
+                vec![other_arg, item],
+            );
+        }
+
+        if let Some(item) = other_item {
+            ptb.move_call(
+                recruit_function(slot.add_function())?,
+                vec![recruit_arg, item],
+            );
+        }
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    /// Attach a new upgrade of the given kind and tier to the recruit's weapon.
+    /// The weapon is taken off the recruit for the upgrade and put back.
+    pub async fn add_weapon_upgrade(
+        &mut self,
+        recruit: WithRef<Recruit>,
+        kind: UpgradeKind,
+        tier: u8,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let recruit_input = self.owned_input(*recruit.object_ref.object_id()).await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let recruit_arg = ptb.input(recruit_input);
+        let tier_arg = ptb.input(Serialized(&tier));
+
+        let weapon = ptb.move_call(recruit_function("remove_weapon")?, vec![recruit_arg]);
+        let upgrade = ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("items")?,
+                Identifier::new(kind.function())?,
                 vec![],
             ),
-            vec![game_arg, path_arg],
+            vec![tier_arg],
         );
 
+        ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("weapon")?,
+                Identifier::new("add_upgrade")?,
+                vec![],
+            ),
+            vec![weapon, upgrade],
+        );
+
+        ptb.move_call(recruit_function("add_weapon")?, vec![recruit_arg, weapon]);
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    /// Detach the upgrade at the given index from the recruit's weapon. The
+    /// upgrade is dropped.
+    pub async fn remove_weapon_upgrade(
+        &mut self,
+        recruit: WithRef<Recruit>,
+        index: u8,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let recruit_input = self.owned_input(*recruit.object_ref.object_id()).await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let recruit_arg = ptb.input(recruit_input);
+        let index_arg = ptb.input(Serialized(&index));
+
+        let weapon = ptb.move_call(recruit_function("remove_weapon")?, vec![recruit_arg]);
+        ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("weapon")?,
+                Identifier::new("remove_upgrade")?,
+                vec![],
+            ),
+            vec![weapon, index_arg],
+        );
+
+        ptb.move_call(recruit_function("add_weapon")?, vec![recruit_arg, weapon]);
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
+    pub async fn move_unit(
+        &mut self,
+        game_id: Address,
+        path: GridPath,
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        self.move_unit_legs(game_id, &[path]).await
+    }
+
+    /// Call `move_unit` for every path in a single transaction, each call
+    /// costs 1 AP. A dash is 2 paths, the second one starts where the first
+    /// one ends.
+    pub async fn move_unit_legs(
+        &mut self,
+        game_id: Address,
+        paths: &[GridPath],
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let game = self.get_shared_object_ref(game_id, true).await?;
+        let game_arg = ptb.input(game);
+
+        for path in paths {
+            let path_arg = ptb.input(Serialized(&path.to_direction_path()));
+
+            ptb.move_call(
+                Function::new(
+                    Address::from_hex(COMMANDER_PKG)?,
+                    Identifier::new("commander")?,
+                    Identifier::new("move_unit")?,
+                    vec![],
+                ),
+                vec![game_arg, path_arg],
+            );
+        }
+
         ptb.set_gas_price(rgp);
         ptb.set_gas_budget(100_000_000);
         ptb.set_sender(self.address);
@@ -289,6 +731,48 @@ impl TxExecutor {
         Ok(self.execute_tx(ptb.finish()?).await?)
     }
 
+    pub async fn perform_grenade(
+        &mut self,
+        game_id: Address,
+        p0: (u8, u8),
+        p1: (u8, u8),
+    ) -> Result<(TransactionEffectsV2, Option<TransactionEvents>), anyhow::Error> {
+        let rgp = self.rgp.unwrap_or(1000);
+        let gas_coins = self.get_gas_coins().await?;
+        let mut ptb = TransactionBuilder::new();
+
+        let game = self.get_shared_object_ref(game_id, true).await?;
+        let rng = self
+            .get_shared_object_ref(Address::from_str("0x8")?, false)
+            .await?;
+
+        let x0 = ptb.input(Serialized(&(p0.0 as u16)));
+        let y0 = ptb.input(Serialized(&(p0.1 as u16)));
+        let x1 = ptb.input(Serialized(&(p1.0 as u16)));
+        let y1 = ptb.input(Serialized(&(p1.1 as u16)));
+
+        let game_arg = ptb.input(game);
+        let rng_arg = ptb.input(rng);
+
+        ptb.move_call(
+            Function::new(
+                Address::from_hex(COMMANDER_PKG)?,
+                Identifier::new("commander")?,
+                Identifier::new("perform_grenade")?,
+                vec![],
+            ),
+            vec![game_arg, rng_arg, x0, y0, x1, y1],
+        );
+
+        ptb.set_gas_price(rgp);
+        ptb.set_gas_budget(100_000_000);
+        ptb.set_sender(self.address);
+        ptb.add_gas_objects(gas_coins.iter().map(|coin| Input::from(coin.clone())));
+        ptb.set_expiration(self.max_epoch);
+
+        self.execute_tx(ptb.finish()?).await
+    }
+
     pub async fn next_turn(
         &mut self,
         game_id: Address,
@@ -355,6 +839,90 @@ impl TxExecutor {
         Ok(self.execute_tx(ptb.finish()?).await?)
     }
 
+    /// Fetch the object refs of the owned recruits in the `squad`.
+    async fn squad_refs(
+        &mut self,
+        squad: &[Option<WithRef<Recruit>>],
+    ) -> Result<Vec<Option<ObjectReference>>, anyhow::Error> {
+        let mut refs = Vec::new();
+        for recruit in squad {
+            refs.push(match recruit {
+                Some(recruit) => Some(
+                    self.get_owned_object_ref(*recruit.object_ref.object_id())
+                        .await?,
+                ),
+                None => None,
+            });
+        }
+
+        Ok(refs)
+    }
+
+    /// Place a recruit on every spawn of the `squad`: the owned recruit if
+    /// there is one, a newly minted recruit otherwise. `commander::place_recruit`
+    /// takes the spawns from the back of the game's positions, so the squad is
+    /// in that order.
+    fn place_squad(
+        ptb: &mut TransactionBuilder,
+        game: Argument,
+        squad: &[Option<ObjectReference>],
+    ) -> Result<(), anyhow::Error> {
+        for recruit_ref in squad {
+            let recruit = match recruit_ref {
+                Some(recruit_ref) => ptb.input(Input::owned(
+                    *recruit_ref.object_id(),
+                    recruit_ref.version(),
+                    *recruit_ref.digest(),
+                )),
+                None => {
+                    let name = ptb.input(Serialized(&"Recruit".to_string()));
+                    let backstory = ptb.input(Serialized(&"Backstory".to_string()));
+
+                    ptb.move_call(
+                        Function::new(
+                            Address::from_hex(COMMANDER_PKG)?,
+                            Identifier::new("recruit")?,
+                            Identifier::new("new")?,
+                            vec![],
+                        ),
+                        vec![name, backstory],
+                    )
+                }
+            };
+
+            ptb.move_call(
+                Function::new(
+                    Address::from_hex(COMMANDER_PKG)?,
+                    Identifier::new("commander")?,
+                    Identifier::new("place_recruit")?,
+                    vec![],
+                ),
+                vec![game, recruit],
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The only new shared object in game creating transactions is the game
+    /// object. Hence, the search for it is straightforward.
+    fn created_game_id(effects: &TransactionEffectsV2) -> Result<Address, anyhow::Error> {
+        Ok(effects
+            .changed_objects
+            .iter()
+            .find(|obj| {
+                matches!(
+                    obj.output_state,
+                    ObjectOut::ObjectWrite {
+                        digest: _,
+                        owner: Owner::Shared(_)
+                    }
+                ) && matches!(obj.id_operation, IdOperation::Created)
+            })
+            .ok_or(anyhow::anyhow!("Game not found in effects"))?
+            .object_id)
+    }
+
     async fn get_gas_coins(&mut self) -> Result<Vec<WithRef<Coin>>, anyhow::Error> {
         match &self.coins {
             Some(coins) => Ok(coins.clone()),
@@ -447,6 +1015,16 @@ impl TxExecutor {
         }
     }
 
+    /// Get the `Input` for an owned object, using the cached ref if available.
+    async fn owned_input(&mut self, id: Address) -> Result<Input, anyhow::Error> {
+        let object_ref = self.get_owned_object_ref(id).await?;
+        Ok(Input::owned(
+            *object_ref.object_id(),
+            object_ref.version(),
+            *object_ref.digest(),
+        ))
+    }
+
     async fn clock(&mut self) -> Result<SharedObjectRef, anyhow::Error> {
         Ok(self
             .get_shared_object_ref(Address::from_str("0x6")?, false)
@@ -599,6 +1177,16 @@ impl Into<Input> for SharedObjectRef {
     }
 }
 
+impl From<WithRef<Host>> for Input {
+    fn from(host: WithRef<Host>) -> Self {
+        Input::receiving(
+            *host.object_ref.object_id(),
+            host.object_ref.version(),
+            *host.object_ref.digest(),
+        )
+    }
+}
+
 impl Into<Input> for WithRef<Preset> {
     fn into(self) -> Input {
         Input::receiving(
@@ -1,14 +1,14 @@
 // Copyright (c) Sui Potatoes
 // SPDX-License-Identifier: MIT
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use macroquad::prelude::*;
 
 use crate::{
     config::{TILE_HEIGHT, TILE_WIDTH},
     draw::{Asset, Draw, DrawAt, DrawCommand, Sprite, Texture, ZIndex, get_scale},
-    types::{Direction, ID, Map, Tile, TileType, Unit},
+    types::{Direction, ID, Map, Tile, TileType, Unit, Visibility, line_of_sight},
 };
 
 #[derive(Clone)]
@@ -33,7 +33,13 @@ pub struct GameTile {
 pub struct Target {
     pub position: (u8, u8),
     pub range: u8,
+    /// Hit chance as rolled by the chain, including the cover bonus. `None` if
+    /// there's no attacker or the target is out of the weapon's range.
     pub chance: Option<u8>,
+    /// Defense bonus of the target from the cover, 0, 25 or 50.
+    pub cover: u8,
+    /// Chance of the target to dodge a hit.
+    pub dodge: u8,
     pub target_id: ID,
 }
 
@@ -58,6 +64,37 @@ impl GameMap {
         }
     }
 
+    /// Deep copy of the map. Unlike `clone`, units are not shared with the
+    /// original map, so changes to one don't affect the other.
+    pub fn snapshot(&self) -> Self {
+        let mut copies: HashMap<*const RefCell<Unit>, Rc<RefCell<Unit>>> = HashMap::new();
+        let mut copy = |unit: &Rc<RefCell<Unit>>| {
+            copies
+                .entry(Rc::as_ptr(unit))
+                .or_insert_with(|| Rc::new(RefCell::new(*unit.borrow())))
+                .clone()
+        };
+
+        let grid = self
+            .grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|tile| GameTile {
+                        unit: tile.unit.as_ref().map(&mut copy),
+                        tile_type: tile.tile_type,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            units: self.units.iter().map(&mut copy).collect(),
+            grid,
+            turn: self.turn,
+        }
+    }
+
     pub fn next_turn(&mut self, update_units: bool) {
         self.turn += 1;
 
@@ -101,6 +138,8 @@ impl GameMap {
         })
     }
 
+    /// Units which the unit at `origin` can shoot at. Units out of the line of
+    /// sight are omitted.
     pub fn targets(&self, origin: (u8, u8)) -> Vec<Target> {
         let mut targets = Vec::new();
         let unit = &self.grid[origin.0 as usize][origin.1 as usize].unit;
@@ -112,11 +151,19 @@ impl GameMap {
                     continue;
                 }
 
-                if let Some(target) = &tile.unit {
+                if let Some(target) = &tile.unit
+                    && line_of_sight(self, origin, (x as u8, y as u8))
+                {
+                    let cover = self.cover_bonus(origin, (x as u8, y as u8));
+
                     targets.push(Target {
                         position: (x as u8, y as u8),
                         range,
-                        chance: unit.clone().map(|unit| chance(&unit.borrow(), range)),
+                        chance: unit
+                            .clone()
+                            .and_then(|unit| hit_chance(&unit.borrow(), range, cover)),
+                        cover,
+                        dodge: target.borrow().stats.dodge().max(0) as u8,
                         target_id: target.borrow().recruit,
                     });
                 }
@@ -125,263 +172,114 @@ impl GameMap {
         targets
     }
 
-    pub fn chebyshev_distance(&self, origin: (u8, u8), target: (u8, u8)) -> u8 {
-        chebyshev_distance(origin, target)
-    }
-
-    pub fn manhattan_distance(&self, origin: (u8, u8), target: (u8, u8)) -> u8 {
-        manhattan_distance(origin, target)
-    }
-
-    /// Use a Dijkstra algorithm to trace a path from `start` to `target` with a
-    /// given `limit`. Use already computed `walkable_tiles` to speed up the
-    /// algorithm.
-    pub fn trace_path(&self, start: (u8, u8), target: (u8, u8)) -> Option<Vec<(u8, u8)>> {
-        let mut map = vec![vec![0; self.cols() as usize]; self.rows() as usize];
-        let mut queue = vec![start];
-        let mut num = 1;
-
-        if start == target {
-            return Some(vec![]);
-        }
-
-        map[start.0 as usize][start.1 as usize] = num;
-
-        'path: while !queue.is_empty() {
-            let temp_queue = queue.drain(..).collect::<Vec<(u8, u8)>>();
-            num += 1;
-
-            for (nx, ny) in temp_queue {
-                for (x, y) in self.von_neumann(nx, ny) {
-                    let to = &self.grid[x as usize][y as usize];
-                    let from = &self.grid[nx as usize][ny as usize];
-                    let direction = Direction::from_coords((nx, ny), (x, y));
-
-                    // if the tile is already visited, skip
-                    // if the tile is an obstacle, skip
-                    // if the tile is a unit, skip
-                    if to.unit.is_some()
-                        || matches!(to.tile_type, TileType::Obstacle)
-                        || map[x as usize][y as usize] != 0
-                    {
-                        continue;
-                    }
-
-                    if let TileType::Cover {
-                        left,
-                        top,
-                        right,
-                        bottom,
-                    } = to.tile_type
-                    {
-                        match direction {
-                            Direction::Up if bottom > 0 => continue,
-                            Direction::Down if top > 0 => continue,
-                            Direction::Left if right > 0 => continue,
-                            Direction::Right if left > 0 => continue,
-                            _ => {}
-                        }
-                    }
-
-                    if let TileType::Cover {
-                        left,
-                        top,
-                        right,
-                        bottom,
-                    } = from.tile_type
-                    {
-                        match direction {
-                            Direction::Up if top > 0 => continue,
-                            Direction::Down if bottom > 0 => continue,
-                            Direction::Left if left > 0 => continue,
-                            Direction::Right if right > 0 => continue,
-                            _ => {}
-                        }
-                    }
-
-                    if x == target.0 && y == target.1 {
-                        map[x as usize][y as usize] = num;
-                        break 'path;
-                    }
-
-                    map[x as usize][y as usize] = num;
-                    queue.push((x, y));
+    /// Defense bonus of the unit at `target` when attacked from `origin`. Mirrors
+    /// `map::cover_bonus`: the cover on the target tile facing the attacker is
+    /// used, if there's none, the neighbouring tile's cover facing the target
+    /// counts. Diagonal attacks take the best of the two sides.
+    pub fn cover_bonus(&self, origin: (u8, u8), target: (u8, u8)) -> u8 {
+        let (x1, y1) = (target.0 as i16, target.1 as i16);
+        let (up, down, left, right) = self.tile_cover(x1, y1);
+
+        let cover_type = match attack_direction(origin, target) {
+            (Direction::None, Direction::None) => return 0,
+            (Direction::Up, Direction::None) => {
+                if down != 0 {
+                    down
+                } else {
+                    self.tile_cover(x1 + 1, y1).0
                 }
             }
-        }
-
-        if map[target.0 as usize][target.1 as usize] == 0 {
-            return None;
-        }
-
-        let (mut nx, mut ny) = target;
-        let mut path = vec![target];
-        let mut num2 = map[target.0 as usize][target.1 as usize];
-
-        while num2 > 1 {
-            for (x, y) in self.von_neumann(nx, ny) {
-                if map[x as usize][y as usize] == num2 - 1 {
-                    let direction = Direction::from_coords((nx, ny), (x, y));
-                    let from = &self.grid[nx as usize][ny as usize];
-                    let to = &self.grid[x as usize][y as usize];
-
-                    if let TileType::Cover {
-                        left,
-                        top,
-                        right,
-                        bottom,
-                    } = from.tile_type
-                    {
-                        match direction {
-                            Direction::Up if top > 0 => continue,
-                            Direction::Down if bottom > 0 => continue,
-                            Direction::Left if left > 0 => continue,
-                            Direction::Right if right > 0 => continue,
-                            _ => {}
-                        }
-                    }
-
-                    if let TileType::Cover {
-                        left,
-                        top,
-                        right,
-                        bottom,
-                    } = to.tile_type
-                    {
-                        match direction {
-                            Direction::Up if bottom > 0 => continue,
-                            Direction::Down if top > 0 => continue,
-                            Direction::Left if right > 0 => continue,
-                            Direction::Right if left > 0 => continue,
-                            _ => {}
-                        }
-                    }
-
-                    nx = x;
-                    ny = y;
-
-                    path.push((x, y));
-                    num2 -= 1;
-                    break;
+            (Direction::Down, Direction::None) => {
+                if up != 0 {
+                    up
+                } else {
+                    self.tile_cover(x1 - 1, y1).1
                 }
             }
-        }
-
-        path.reverse();
-        Some(path)
-    }
-
-    pub fn walkable_tiles(&self, start: (u8, u8), limit: u8) -> Vec<(u8, u8)> {
-        let mut tiles = Vec::new();
-        let mut map = vec![vec![0; self.cols() as usize]; self.rows() as usize];
-        let mut queue = vec![start];
-        let mut num = 1;
-
-        map[start.0 as usize][start.1 as usize] = num;
-
-        while !queue.is_empty() {
-            let temp_queue = queue.drain(..).collect::<Vec<(u8, u8)>>();
-            num += 1;
-
-            if num > limit + 1 {
-                break;
+            (Direction::None, Direction::Left) => {
+                if right != 0 {
+                    right
+                } else {
+                    self.tile_cover(x1, y1 + 1).2
+                }
             }
-
-            for (nx, ny) in temp_queue {
-                for (x, y) in self.von_neumann(nx, ny) {
-                    let to = &self.grid[x as usize][y as usize];
-                    let from = &self.grid[nx as usize][ny as usize];
-                    let direction = Direction::from_coords((nx, ny), (x, y));
-
-                    if to.unit.is_some()
-                        || matches!(to.tile_type, TileType::Obstacle)
-                        || map[x as usize][y as usize] != 0
-                    {
-                        continue;
-                    }
-
-                    if let TileType::Cover {
-                        left,
-                        top,
-                        right,
-                        bottom,
-                    } = to.tile_type
-                    {
-                        match direction {
-                            Direction::Up if bottom > 0 => continue,
-                            Direction::Down if top > 0 => continue,
-                            Direction::Left if right > 0 => continue,
-                            Direction::Right if left > 0 => continue,
-                            _ => {}
-                        }
-                    }
-
-                    if let TileType::Cover {
-                        left,
-                        top,
-                        right,
-                        bottom,
-                    } = from.tile_type
-                    {
-                        match direction {
-                            Direction::Up if top > 0 => continue,
-                            Direction::Down if bottom > 0 => continue,
-                            Direction::Left if left > 0 => continue,
-                            Direction::Right if right > 0 => continue,
-                            _ => {}
-                        }
-                    }
-
-                    if map[x as usize][y as usize] == 0 {
-                        map[x as usize][y as usize] = num;
-                        queue.push((x, y));
-                        tiles.push((x, y));
-                    }
+            (Direction::None, Direction::Right) => {
+                if left != 0 {
+                    left
+                } else {
+                    self.tile_cover(x1, y1 - 1).3
                 }
             }
-        }
-
-        tiles
-    }
+            (Direction::Up, Direction::Left) => match down.max(right) {
+                0 => self
+                    .tile_cover(x1 + 1, y1)
+                    .0
+                    .max(self.tile_cover(x1, y1 + 1).2),
+                cover_type => cover_type,
+            },
+            (Direction::Up, Direction::Right) => match down.max(left) {
+                0 => self
+                    .tile_cover(x1 + 1, y1)
+                    .0
+                    .max(self.tile_cover(x1, y1 - 1).3),
+                cover_type => cover_type,
+            },
+            (Direction::Down, Direction::Left) => match up.max(right) {
+                0 => self
+                    .tile_cover(x1 - 1, y1)
+                    .1
+                    .max(self.tile_cover(x1, y1 + 1).2),
+                cover_type => cover_type,
+            },
+            (Direction::Down, Direction::Right) => match up.max(left) {
+                0 => self
+                    .tile_cover(x1 - 1, y1)
+                    .1
+                    .max(self.tile_cover(x1, y1 - 1).3),
+                cover_type => cover_type,
+            },
+            _ => unreachable!("Invalid attack direction"),
+        };
 
-    fn coords_to_direction(&self, from: (u8, u8), to: (u8, u8)) -> Direction {
-        let (x0, y0) = from;
-        let (x1, y1) = to;
-
-        if x0 < x1 {
-            Direction::Down
-        } else if x0 > x1 {
-            Direction::Up
-        } else if y0 > y1 {
-            Direction::Left
-        } else if y0 < y1 {
-            Direction::Right
-        } else {
-            panic!("Invalid direction");
-        }
+        cover_type * DEFENSE_BONUS
     }
 
-    fn von_neumann(&self, x: u8, y: u8) -> Vec<(u8, u8)> {
-        let mut points = Vec::new();
-        let (rows, cols) = self.dimensions();
-
-        if x < rows - 1 {
-            points.push((x + 1, y));
+    /// Cover sides of the tile in the Move order: (top, bottom, left, right).
+    /// Tiles outside of the grid have no cover.
+    fn tile_cover(&self, x: i16, y: i16) -> (u8, u8, u8, u8) {
+        if x < 0 || y < 0 || x >= self.rows() as i16 || y >= self.cols() as i16 {
+            return (0, 0, 0, 0);
         }
 
-        if y < cols - 1 {
-            points.push((x, y + 1));
+        match self.grid[x as usize][y as usize].tile_type {
+            TileType::Cover {
+                left,
+                top,
+                right,
+                bottom,
+            } => (top, bottom, left, right),
+            _ => (0, 0, 0, 0),
         }
+    }
 
-        if x > 0 {
-            points.push((x - 1, y));
-        }
+    /// Tiles affected by a grenade thrown at `target`, in the same order as
+    /// `map::perform_grenade` visits them (`cell::von_neumann_neighbors` followed
+    /// by the center). The order matters: the chain emits one `Damage` record
+    /// per unit it meets, without positions.
+    pub fn grenade_area(&self, target: (u8, u8), radius: u8) -> Vec<(u8, u8)> {
+        let (rows, cols) = (self.rows(), self.cols());
+        grenade_area(target, radius)
+            .into_iter()
+            .filter(|(x, y)| *x < rows && *y < cols)
+            .collect()
+    }
 
-        if y > 0 {
-            points.push((x, y - 1));
-        }
+    pub fn chebyshev_distance(&self, origin: (u8, u8), target: (u8, u8)) -> u8 {
+        chebyshev_distance(origin, target)
+    }
 
-        points
+    pub fn manhattan_distance(&self, origin: (u8, u8), target: (u8, u8)) -> u8 {
+        manhattan_distance(origin, target)
     }
 }
 
@@ -430,23 +328,67 @@ impl Into<Map> for GameMap {
     }
 }
 
+/// Tint of the remembered terrain which is out of sight.
+const STALE_TINT: Color = Color::new(0.5, 0.5, 0.5, 1.0);
+
 impl Draw for GameMap {
     fn draw(&self) {
+        self.draw_tiles(None);
+    }
+}
+
+impl GameMap {
+    /// Draw the map under the fog of war: tiles never seen are covered, tiles
+    /// out of sight are dimmed. Units are drawn separately as `GameObject`s.
+    pub fn draw_with_visibility(&self, visibility: &Visibility) {
+        self.draw_tiles(Some(visibility));
+    }
+
+    fn draw_tiles(&self, visibility: Option<&Visibility>) {
         let (scale_x, scale_y) = get_scale(self.dimensions());
         for (y, row) in self.grid.iter().enumerate() {
             for (x, tile) in row.iter().enumerate() {
-                DrawCommand::rectangle_lines(
+                let (px, py) = (
                     x as f32 * TILE_WIDTH * scale_x,
                     y as f32 * TILE_HEIGHT * scale_y,
-                    TILE_WIDTH * scale_x,
-                    TILE_HEIGHT * scale_y,
-                )
-                .color(DARKGRAY)
-                .thickness(1.0)
-                .z_index(ZIndex::Grid)
-                .schedule();
-
-                tile.draw_at((x as u8, y as u8), self.dimensions());
+                );
+
+                DrawCommand::rectangle_lines(px, py, TILE_WIDTH * scale_x, TILE_HEIGHT * scale_y)
+                    .color(DARKGRAY)
+                    .thickness(1.0)
+                    .z_index(ZIndex::Grid)
+                    .schedule();
+
+                // Unlike the screen, the grid is indexed by the row first.
+                let position = (y as u8, x as u8);
+                // Tiles out of sight are drawn as they were last seen.
+                let (fog, terrain) = match visibility {
+                    None => (None, Some((tile.tile_type, WHITE))),
+                    Some(v) if v.is_visible(position) => (None, Some((tile.tile_type, WHITE))),
+                    Some(v) => match v.remembered(position) {
+                        Some(tile_type) => {
+                            (Some(BLACK.with_alpha(0.5)), Some((tile_type, STALE_TINT)))
+                        }
+                        // Nothing is known about the unexplored tiles.
+                        None => (Some(BLACK), None),
+                    },
+                };
+
+                if let Some(color) = fog {
+                    DrawCommand::rectangle(px, py, TILE_WIDTH * scale_x, TILE_HEIGHT * scale_y)
+                        .color(color)
+                        .z_index(ZIndex::Fog)
+                        .schedule();
+                }
+
+                if let Some((tile_type, tint)) = terrain {
+                    let tile = GameTile {
+                        unit: None,
+                        tile_type,
+                    };
+
+                    tile.draw_tinted((x as u8, y as u8), self.dimensions(), tint);
+                }
             }
         }
     }
@@ -454,6 +396,13 @@ impl Draw for GameMap {
 
 impl DrawAt for GameTile {
     fn draw_at(&self, position: (u8, u8), dimensions: (u8, u8)) {
+        self.draw_tinted(position, dimensions, WHITE);
+    }
+}
+
+impl GameTile {
+    /// Draw the tile with its textures tinted with the `color`.
+    pub fn draw_tinted(&self, position: (u8, u8), dimensions: (u8, u8), color: Color) {
         let (scale_x, scale_y) = get_scale(dimensions);
         let (x, y) = (
             position.0 as f32 * TILE_WIDTH * scale_x,
@@ -467,7 +416,7 @@ impl DrawAt for GameTile {
 
                 DrawCommand::texture(texture)
                     .position(x, y)
-                    .color(WHITE)
+                    .color(color)
                     .dest_size(Vec2::new(TILE_WIDTH * scale_x, TILE_HEIGHT * scale_y))
                     .z_index(ZIndex::Obstacle)
                     .schedule();
@@ -483,25 +432,25 @@ impl DrawAt for GameTile {
 
                 if *left > 0 {
                     sprite
-                        .draw_frame_with_index(x, y, 0, WHITE, dimensions, ZIndex::TopCover)
+                        .draw_frame_with_index(x, y, 0, color, dimensions, ZIndex::TopCover)
                         .schedule();
                 }
 
                 if *top > 0 {
                     sprite
-                        .draw_frame_with_index(x, y, 1, WHITE, dimensions, ZIndex::TopCover)
+                        .draw_frame_with_index(x, y, 1, color, dimensions, ZIndex::TopCover)
                         .schedule();
                 }
 
                 if *right > 0 {
                     sprite
-                        .draw_frame_with_index(x, y, 2, WHITE, dimensions, ZIndex::TopCover)
+                        .draw_frame_with_index(x, y, 2, color, dimensions, ZIndex::TopCover)
                         .schedule();
                 }
 
                 if *bottom > 0 {
                     sprite
-                        .draw_frame_with_index(x, y, 3, WHITE, dimensions, ZIndex::BottomCover)
+                        .draw_frame_with_index(x, y, 3, color, dimensions, ZIndex::BottomCover)
                         .schedule();
                 }
             }
@@ -509,6 +458,49 @@ impl DrawAt for GameTile {
     }
 }
 
+/// Tiles affected by a grenade thrown at `target` on a map of any size, see
+/// `GameMap::grenade_area` for the order of the tiles.
+pub fn grenade_area(target: (u8, u8), radius: u8) -> Vec<(u8, u8)> {
+    let (x, y) = (target.0 as i16, target.1 as i16);
+    let mut cells = match radius {
+        0 => vec![],
+        1 => vec![(x - 1, y), (x, y - 1), (x + 1, y), (x, y + 1)],
+        2 => vec![
+            (x - 2, y),
+            (x, y - 2),
+            (x - 1, y),
+            (x, y - 1),
+            (x + 1, y),
+            (x, y + 1),
+            (x + 2, y),
+            (x, y + 2),
+            (x - 1, y - 1),
+            (x - 1, y + 1),
+            (x + 1, y - 1),
+            (x + 1, y + 1),
+        ],
+        r => {
+            let r = r as i16;
+            let mut cells = Vec::new();
+            for cx in x - r..=x + r {
+                for cy in y - r..=y + r {
+                    let distance = (cx - x).abs() + (cy - y).abs();
+                    if distance > 0 && distance <= r {
+                        cells.push((cx, cy));
+                    }
+                }
+            }
+            cells
+        }
+    };
+
+    cells.push((x, y));
+    cells
+        .into_iter()
+        .filter_map(|(x, y)| Some((u8::try_from(x).ok()?, u8::try_from(y).ok()?)))
+        .collect()
+}
+
 pub fn chebyshev_distance(origin: (u8, u8), target: (u8, u8)) -> u8 {
     let (x0, y0) = origin;
     let (x1, y1) = target;
@@ -521,21 +513,70 @@ pub fn manhattan_distance(origin: (u8, u8), target: (u8, u8)) -> u8 {
     x0.abs_diff(x1) + y0.abs_diff(y1)
 }
 
+/// Maximum distance (Manhattan) of a grenade throw.
+pub const GRENADE_RANGE: u8 = 5;
+/// Radius of the grenade's area of effect.
+pub const GRENADE_RADIUS: u8 = 2;
+/// Damage dealt by a grenade to every unit in the area, before armor.
+pub const GRENADE_DAMAGE: u8 = 4;
+
 const CLOSE_DISTANCE_MODIFIER: u8 = 5;
 const DISTANCE_MODIFIER: u8 = 10;
-// const MAX_DISTANCE_OFFSET: u8 = 3;
-
-fn chance(unit: &Unit, range: u8) -> u8 {
-    let aim = unit.stats.aim() as u8;
-    let eff_range = unit.stats.range() as u8;
+const MAX_DISTANCE_OFFSET: u8 = 3;
+/// Defense bonus for each level of cover (low - 25, high - 50).
+const DEFENSE_BONUS: u8 = 25;
+
+/// Hit chance of the `unit` at the given `range` against a target with the
+/// `defense` bonus. Mirrors `unit::perform_attack`, returns `None` if the
+/// target is out of range and the attack would abort.
+///
+/// Note: the chain does not use the target's `defense` stat, only the cover.
+pub fn hit_chance(unit: &Unit, range: u8, defense: u8) -> Option<u8> {
+    let aim = unit.stats.aim().max(0) as u8;
+    let eff_range = unit.stats.range().max(0) as u8;
+
+    if eff_range + MAX_DISTANCE_OFFSET < range {
+        return None;
+    }
 
-    if range == eff_range {
-        return aim;
+    let chance = if range == eff_range {
+        aim
     } else if range < eff_range {
-        return aim + (eff_range - range) * CLOSE_DISTANCE_MODIFIER;
+        (aim + (eff_range - range) * CLOSE_DISTANCE_MODIFIER).min(100)
     } else {
         let diff = (range - eff_range) * DISTANCE_MODIFIER;
-        return aim - aim.min(diff);
+        aim - aim.min(diff)
+    };
+
+    Some(chance - chance.min(defense))
+}
+
+/// Direction of the attack as a (vertical, horizontal) pair, following the
+/// `direction!` macro in the grid package: the dominating axis wins, equal
+/// offsets produce a diagonal.
+fn attack_direction(origin: (u8, u8), target: (u8, u8)) -> (Direction, Direction) {
+    let (x0, y0) = origin;
+    let (x1, y1) = target;
+    let (diff_x, diff_y) = (x0.abs_diff(x1), y0.abs_diff(y1));
+
+    let vertical = match x0.cmp(&x1) {
+        std::cmp::Ordering::Less => Direction::Down,
+        std::cmp::Ordering::Greater => Direction::Up,
+        std::cmp::Ordering::Equal => Direction::None,
+    };
+
+    let horizontal = match y0.cmp(&y1) {
+        std::cmp::Ordering::Less => Direction::Right,
+        std::cmp::Ordering::Greater => Direction::Left,
+        std::cmp::Ordering::Equal => Direction::None,
+    };
+
+    if diff_x > diff_y {
+        (vertical, Direction::None)
+    } else if diff_y > diff_x {
+        (Direction::None, horizontal)
+    } else {
+        (vertical, horizontal)
     }
 }
 
@@ -566,3 +607,196 @@ fn test_game_map() {
     assert_eq!(game_map.grid[0][0].unit.as_ref().unwrap().borrow().ap.0, 10);
     assert_eq!(game_map.units[0].borrow().ap.0, 10);
 }
+
+#[test]
+fn test_grenade_area() {
+    let map = GameMap::new(10, 10);
+
+    // Same order as `cell::von_neumann_neighbors` + the center in Move.
+    assert_eq!(
+        map.grenade_area((5, 5), 2),
+        vec![
+            (3, 5),
+            (5, 3),
+            (4, 5),
+            (5, 4),
+            (6, 5),
+            (5, 6),
+            (7, 5),
+            (5, 7),
+            (4, 4),
+            (4, 6),
+            (6, 4),
+            (6, 6),
+            (5, 5),
+        ]
+    );
+
+    // Cells outside of the grid are filtered out.
+    assert_eq!(
+        map.grenade_area((0, 9), 2),
+        vec![(0, 7), (0, 8), (1, 9), (2, 9), (1, 8), (0, 9)]
+    );
+}
+
+#[test]
+fn test_cover_system_a() {
+    let (no, low, high) = (0, 1, 2);
+    let mut map = GameMap::new(3, 3);
+
+    assert_eq!(map.cover_bonus((2, 0), (0, 0)), 0); // attack up
+    assert_eq!(map.cover_bonus((0, 2), (0, 0)), 0); // attack left
+    assert_eq!(map.cover_bonus((2, 2), (0, 0)), 0); // attack up-left
+
+    // top-left corner, protected by covers
+    map.grid[0][0].tile_type = TileType::Cover {
+        left: no,
+        top: no,
+        right: low,
+        bottom: low,
+    };
+
+    assert_eq!(map.cover_bonus((2, 0), (0, 0)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((0, 2), (0, 0)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((2, 2), (0, 0)), DEFENSE_BONUS);
+
+    // replace the top-left cover with high cover
+    map.grid[0][0].tile_type = TileType::Cover {
+        left: no,
+        top: no,
+        right: high,
+        bottom: high,
+    };
+
+    assert_eq!(map.cover_bonus((2, 0), (0, 0)), 2 * DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((0, 2), (0, 0)), 2 * DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((2, 2), (0, 0)), 2 * DEFENSE_BONUS);
+
+    // remove the cover on the target tile, and place on neighboring tiles
+    map.grid[0][0].tile_type = TileType::Empty;
+    map.grid[1][0].tile_type = TileType::Cover {
+        left: no,
+        top: low,
+        right: no,
+        bottom: no,
+    };
+    map.grid[0][1].tile_type = TileType::Cover {
+        left: low,
+        top: no,
+        right: no,
+        bottom: no,
+    };
+
+    assert_eq!(map.cover_bonus((2, 0), (0, 0)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((0, 2), (0, 0)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((2, 2), (0, 0)), DEFENSE_BONUS);
+
+    // replace one of the covers with high cover and attack diagonally
+    map.grid[0][1].tile_type = TileType::Cover {
+        left: high,
+        top: no,
+        right: no,
+        bottom: no,
+    };
+
+    assert_eq!(map.cover_bonus((2, 2), (0, 0)), 2 * DEFENSE_BONUS);
+}
+
+#[test]
+fn test_cover_system_b() {
+    let (no, low, high) = (0, 1, 2);
+    let mut map = GameMap::new(3, 3);
+
+    assert_eq!(map.cover_bonus((0, 2), (2, 2)), 0); // attack down
+    assert_eq!(map.cover_bonus((2, 0), (2, 2)), 0); // attack right
+    assert_eq!(map.cover_bonus((0, 0), (2, 2)), 0); // attack down-right
+
+    // bottom-right corner, protected by covers
+    map.grid[2][2].tile_type = TileType::Cover {
+        left: low,
+        top: low,
+        right: no,
+        bottom: no,
+    };
+
+    assert_eq!(map.cover_bonus((0, 2), (2, 2)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((2, 0), (2, 2)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((0, 0), (2, 2)), DEFENSE_BONUS);
+
+    // replace the cover with high cover
+    map.grid[2][2].tile_type = TileType::Cover {
+        left: high,
+        top: high,
+        right: no,
+        bottom: no,
+    };
+
+    assert_eq!(map.cover_bonus((0, 2), (2, 2)), 2 * DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((2, 0), (2, 2)), 2 * DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((0, 0), (2, 2)), 2 * DEFENSE_BONUS);
+
+    // remove the cover on the target tile, and place on neighboring tiles
+    map.grid[2][2].tile_type = TileType::Empty;
+    map.grid[1][2].tile_type = TileType::Cover {
+        left: no,
+        top: no,
+        right: no,
+        bottom: low,
+    };
+    map.grid[2][1].tile_type = TileType::Cover {
+        left: no,
+        top: no,
+        right: low,
+        bottom: no,
+    };
+
+    assert_eq!(map.cover_bonus((2, 0), (2, 2)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((0, 2), (2, 2)), DEFENSE_BONUS);
+    assert_eq!(map.cover_bonus((0, 0), (2, 2)), DEFENSE_BONUS);
+
+    // replace one of the covers with high cover and attack diagonally
+    map.grid[2][1].tile_type = TileType::Cover {
+        left: no,
+        top: no,
+        right: high,
+        bottom: no,
+    };
+
+    assert_eq!(map.cover_bonus((0, 0), (2, 2)), 2 * DEFENSE_BONUS);
+}
+
+#[test]
+fn test_hit_chance() {
+    let unit = Unit::default();
+    let aim = unit.stats.aim() as u8;
+    let range = unit.stats.range() as u8;
+
+    assert_eq!(hit_chance(&unit, range, 0), Some(aim));
+    assert_eq!(
+        hit_chance(&unit, range, DEFENSE_BONUS),
+        Some(aim - DEFENSE_BONUS)
+    );
+    assert_eq!(
+        hit_chance(&unit, range + 1, 0),
+        Some(aim - DISTANCE_MODIFIER)
+    );
+    assert_eq!(hit_chance(&unit, range + MAX_DISTANCE_OFFSET + 1, 0), None);
+    assert_eq!(hit_chance(&unit, 1, 100), Some(0));
+}
+
+#[test]
+fn test_game_map_snapshot() {
+    let mut map = GameMap::new(3, 3);
+    let unit = Rc::new(RefCell::new(Unit::default()));
+    map.grid[1][1].unit = Some(unit.clone());
+    map.units.push(unit.clone());
+
+    let snapshot = map.snapshot();
+    unit.borrow_mut().hp.decrease(5);
+    map.grid[0][0].tile_type = TileType::Obstacle;
+
+    let copy = snapshot.grid[1][1].unit.as_ref().unwrap();
+    assert_eq!(copy.borrow().hp.value(), 8);
+    assert!(Rc::ptr_eq(copy, &snapshot.units[0]));
+    assert!(matches!(snapshot.grid[0][0].tile_type, TileType::Empty));
+}
@@ -0,0 +1,207 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Mirrors the `items` module in Move: equipment slots of a `Recruit`, weapon
+//! upgrades and the way equipment affects the final `Stats` of a `Unit`.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Recruit, Stats, Weapon, WeaponUpgrade};
+
+/// Maximum number of upgrades a `Weapon` can have.
+pub const MAX_WEAPON_UPGRADES: usize = 3;
+
+/// Equipment slot of a `Recruit`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Slot {
+    Weapon,
+    Armor,
+}
+
+/// Kinds of `WeaponUpgrade`s available in the `items` module. Each kind comes
+/// in three tiers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UpgradeKind {
+    Scope,
+    LaserSight,
+    Stock,
+    ExpandedClip,
+}
+
+impl Slot {
+    /// Name of the Move function which adds an item to this slot.
+    pub fn add_function(&self) -> &str {
+        match self {
+            Slot::Weapon => "add_weapon",
+            Slot::Armor => "add_armor",
+        }
+    }
+
+    /// Name of the Move function which removes an item from this slot.
+    pub fn remove_function(&self) -> &str {
+        match self {
+            Slot::Weapon => "remove_weapon",
+            Slot::Armor => "remove_armor",
+        }
+    }
+
+    /// Check if the `Recruit` has an item in this slot.
+    pub fn is_equipped(&self, recruit: &Recruit) -> bool {
+        match self {
+            Slot::Weapon => recruit.weapon.is_some(),
+            Slot::Armor => recruit.armor.is_some(),
+        }
+    }
+
+    /// Name of the item in this slot, if any.
+    pub fn item_name<'a>(&self, recruit: &'a Recruit) -> Option<&'a str> {
+        match self {
+            Slot::Weapon => recruit.weapon.as_ref().map(|w| w.name.as_str()),
+            Slot::Armor => recruit.armor.as_ref().map(|a| a.name.as_str()),
+        }
+    }
+}
+
+impl UpgradeKind {
+    pub const ALL: [UpgradeKind; 4] = [
+        UpgradeKind::Scope,
+        UpgradeKind::LaserSight,
+        UpgradeKind::Stock,
+        UpgradeKind::ExpandedClip,
+    ];
+
+    /// Name of the Move function in the `items` module creating the upgrade.
+    pub fn function(&self) -> &str {
+        match self {
+            UpgradeKind::Scope => "scope",
+            UpgradeKind::LaserSight => "laser_sight",
+            UpgradeKind::Stock => "stock",
+            UpgradeKind::ExpandedClip => "expanded_clip",
+        }
+    }
+
+    /// Create the `WeaponUpgrade` of the given tier (1-3), the same way it is
+    /// created in Move. Returns `None` for an invalid tier.
+    pub fn upgrade(&self, tier: u8) -> Option<WeaponUpgrade> {
+        if !(1..=3).contains(&tier) {
+            return None;
+        }
+
+        let (names, offset, step) = match self {
+            UpgradeKind::Scope => (["Basic", "Advanced", "Superior"], 1, 5),
+            UpgradeKind::LaserSight => (["Basic", "Advanced", "Sniper"], 9, 5),
+            UpgradeKind::Stock => (["Basic", "Advanced", "Sniper"], 13, 1),
+            UpgradeKind::ExpandedClip => (["Basic", "Advanced", "Superior"], 14, 1),
+        };
+
+        Some(WeaponUpgrade {
+            name: format!("{} {}", names[tier as usize - 1], self),
+            tier,
+            stats: Stats::new_unchecked(((step * tier) as u128) << (offset * 8)),
+        })
+    }
+}
+
+impl Weapon {
+    /// Attach the upgrade to the weapon, modifying its stats. Mirrors
+    /// `weapon::add_upgrade`, returns `false` if the weapon is fully upgraded.
+    pub fn add_upgrade(&mut self, upgrade: WeaponUpgrade) -> bool {
+        if self.upgrades.len() >= MAX_WEAPON_UPGRADES {
+            return false;
+        }
+
+        self.stats = self.stats.add(&upgrade.stats);
+        self.upgrades.push(upgrade);
+        true
+    }
+
+    /// Detach the upgrade at the given index, reverting its effect on stats.
+    /// Mirrors `weapon::remove_upgrade`.
+    pub fn remove_upgrade(&mut self, index: usize) -> Option<WeaponUpgrade> {
+        if index >= self.upgrades.len() {
+            return None;
+        }
+
+        let upgrade = self.upgrades.remove(index);
+        self.stats = self.stats.add(&upgrade.stats.negate());
+        Some(upgrade)
+    }
+}
+
+impl Recruit {
+    /// Stats of the `Unit` created from this `Recruit`: recruit stats combined
+    /// with the weapon (or the default one) and the armor. Mirrors
+    /// `unit::from_recruit`.
+    pub fn unit_stats(&self) -> Stats {
+        let weapon = self
+            .weapon
+            .as_ref()
+            .map_or(Stats::default_weapon(), |w| w.stats);
+        let armor = self
+            .armor
+            .as_ref()
+            .map_or(Stats::default_armor(), |a| a.stats);
+
+        self.stats.add(&weapon.add(&armor))
+    }
+}
+
+impl Display for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Slot::Weapon => write!(f, "Weapon"),
+            Slot::Armor => write!(f, "Armor"),
+        }
+    }
+}
+
+impl Display for UpgradeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpgradeKind::Scope => write!(f, "Scope"),
+            UpgradeKind::LaserSight => write!(f, "Laser Sight"),
+            UpgradeKind::Stock => write!(f, "Stock"),
+            UpgradeKind::ExpandedClip => write!(f, "Expanded Clip"),
+        }
+    }
+}
+
+#[test]
+fn test_upgrade_stats() {
+    let scope = UpgradeKind::Scope.upgrade(2).unwrap();
+    assert_eq!(scope.name, "Advanced Scope");
+    assert_eq!(scope.stats.aim(), 10);
+
+    let clip = UpgradeKind::ExpandedClip.upgrade(3).unwrap();
+    assert_eq!(clip.name, "Superior Expanded Clip");
+    assert_eq!(clip.stats.ammo(), 3);
+
+    assert!(UpgradeKind::Stock.upgrade(0).is_none());
+    assert!(UpgradeKind::Stock.upgrade(4).is_none());
+}
+
+#[test]
+fn test_weapon_upgrades() {
+    let mut weapon = Weapon {
+        id: Default::default(),
+        name: "Standard Rifle".to_string(),
+        stats: Stats::default_weapon(),
+        upgrades: vec![],
+    };
+
+    assert!(weapon.add_upgrade(UpgradeKind::Stock.upgrade(1).unwrap()));
+    assert!(weapon.add_upgrade(UpgradeKind::LaserSight.upgrade(3).unwrap()));
+    assert_eq!(weapon.stats.range(), 5);
+    assert_eq!(weapon.stats.crit_chance(), 15);
+
+    assert!(weapon.add_upgrade(UpgradeKind::Scope.upgrade(1).unwrap()));
+    assert!(!weapon.add_upgrade(UpgradeKind::Scope.upgrade(1).unwrap()));
+
+    let stock = weapon.remove_upgrade(0).unwrap();
+    assert_eq!(stock.name, "Basic Stock");
+    assert_eq!(weapon.stats.range(), 4);
+    assert_eq!(weapon.upgrades.len(), 2);
+    assert!(weapon.remove_upgrade(2).is_none());
+}
@@ -0,0 +1,143 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Line of sight between two tiles, traced through the grid from the center of
+//! one tile to the center of the other. The contract doesn't check it, it is
+//! used to only offer the shots which make sense.
+//!
+//! Obstacles and high cover crossed by the line block the sight. The cover
+//! right next to the attacker and the target doesn't: the attacker leans out
+//! of its cover, and the target's cover gives it a defense bonus instead.
+
+use super::{Direction, GameMap, TileType, blocks, tile};
+
+/// Whether the unit at `origin` can see the `target` tile.
+pub fn line_of_sight(map: &GameMap, origin: (u8, u8), target: (u8, u8)) -> bool {
+    if tile(map, origin).is_none() || tile(map, target).is_none() {
+        return false;
+    }
+
+    let (dx, dy) = (
+        origin.0.abs_diff(target.0) as i32,
+        origin.1.abs_diff(target.1) as i32,
+    );
+    let step = |a: u8, b: u8| if a < b { 1 } else { -1 };
+    let (sx, sy) = (step(origin.0, target.0), step(origin.1, target.1));
+    let offset =
+        |(x, y): (u8, u8), ox: i16, oy: i16| ((x as i16 + ox) as u8, (y as i16 + oy) as u8);
+
+    let (mut ix, mut iy) = (0, 0);
+    let mut position = origin;
+
+    while ix < dx || iy < dy {
+        // Compares where the line crosses the next row and the next column.
+        let decision = (1 + 2 * ix) * dy - (1 + 2 * iy) * dx;
+
+        let next = match decision {
+            // The line passes through the corner, either way around will do.
+            0 => {
+                let next = offset(position, sx, sy);
+                let (via_x, via_y) = (offset(position, sx, 0), offset(position, 0, sy));
+                let clear = |via| {
+                    clear_step(map, position, via, origin, target)
+                        && clear_step(map, via, next, origin, target)
+                };
+
+                if !clear(via_x) && !clear(via_y) {
+                    return false;
+                }
+
+                ix += 1;
+                iy += 1;
+                next
+            }
+            decision => {
+                let next = match decision < 0 {
+                    true => {
+                        ix += 1;
+                        offset(position, sx, 0)
+                    }
+                    false => {
+                        iy += 1;
+                        offset(position, 0, sy)
+                    }
+                };
+
+                if !clear_step(map, position, next, origin, target) {
+                    return false;
+                }
+
+                next
+            }
+        };
+
+        position = next;
+    }
+
+    true
+}
+
+/// Whether the line can cross from a tile to the adjacent one.
+fn clear_step(
+    map: &GameMap,
+    from: (u8, u8),
+    to: (u8, u8),
+    origin: (u8, u8),
+    target: (u8, u8),
+) -> bool {
+    let (Some(source), Some(destination)) = (tile(map, from), tile(map, to)) else {
+        return false;
+    };
+
+    if to != target && matches!(destination.tile_type, TileType::Obstacle) {
+        return false;
+    }
+
+    if from == origin || to == target {
+        return true;
+    }
+
+    let direction = Direction::from_coords(from, to);
+    !blocks(&source.tile_type, direction) && !blocks(&destination.tile_type, direction.opposite())
+}
+
+#[test]
+fn test_line_of_sight() {
+    use super::HIGH_COVER;
+
+    let wall = |left, right| TileType::Cover {
+        left,
+        top: 0,
+        right,
+        bottom: 0,
+    };
+
+    let mut map = GameMap::new(5, 5);
+    assert!(line_of_sight(&map, (0, 0), (0, 4)));
+    assert!(line_of_sight(&map, (0, 0), (3, 4)));
+    assert!(!line_of_sight(&map, (0, 0), (0, 5))); // off the grid
+
+    map.grid[0][2].tile_type = TileType::Obstacle;
+    assert!(!line_of_sight(&map, (0, 0), (0, 4)));
+
+    // Low cover doesn't block, high cover does.
+    map.grid[0][2].tile_type = wall(1, 0);
+    assert!(line_of_sight(&map, (0, 0), (0, 4)));
+    map.grid[0][2].tile_type = wall(HIGH_COVER, 0);
+    assert!(!line_of_sight(&map, (0, 0), (0, 4)));
+
+    // Cover next to the attacker and the target doesn't block.
+    map.grid[0][2].tile_type = TileType::Empty;
+    map.grid[0][1].tile_type = wall(HIGH_COVER, 0);
+    map.grid[0][3].tile_type = wall(0, HIGH_COVER);
+    assert!(line_of_sight(&map, (0, 0), (0, 4)));
+
+    // Through the corner, blocked only if both ways around are.
+    map.grid[1][1].tile_type = TileType::Obstacle;
+    assert!(!line_of_sight(&map, (0, 0), (2, 2)));
+    map.grid[1][1].tile_type = TileType::Empty;
+    map.grid[0][1].tile_type = TileType::Obstacle;
+    assert!(line_of_sight(&map, (0, 0), (2, 2)));
+    map.grid[1][0].tile_type = TileType::Obstacle;
+    assert!(!line_of_sight(&map, (0, 0), (2, 2)));
+}
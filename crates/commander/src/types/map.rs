@@ -47,6 +47,85 @@ impl Map {
     pub fn cols(&self) -> u8 {
         self.grid[0].len() as u8
     }
+
+    /// Serialize the `Map` into the format expected by `map::from_bytes` in
+    /// Move. The Move `Grid` also stores its dimensions after the tiles.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bcs::to_bytes(&MoveMap::from(self)).unwrap()
+    }
+
+    /// Deserialize the `Map` from the bytes accepted by `map::from_bytes` in
+    /// Move, such as the ones produced by `Map::to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        bcs::from_bytes::<MoveMap>(bytes)?.try_into()
+    }
+
+    /// Serialize the `Map` followed by the spawn positions, the format of the
+    /// `bytes` argument of `commander::publish_map`.
+    pub fn to_publish_bytes(&self, positions: &[Vec<u8>]) -> Vec<u8> {
+        bcs::to_bytes(&PublishBytes {
+            map: self.into(),
+            positions: positions.to_vec(),
+        })
+        .unwrap()
+    }
+
+    /// Deserialize the `Map` and the spawn positions produced by
+    /// `Map::to_publish_bytes`.
+    pub fn from_publish_bytes(bytes: &[u8]) -> Result<(Self, Vec<Vec<u8>>), anyhow::Error> {
+        let PublishBytes { map, positions } = bcs::from_bytes(bytes)?;
+        Ok((map.try_into()?, positions))
+    }
+}
+
+/// Layout of the `Map` in Move, where the `Grid` stores its dimensions after
+/// the tiles.
+#[derive(Serialize, Deserialize)]
+struct MoveMap {
+    id: ID,
+    grid: Vec<Vec<Tile>>,
+    rows: u16,
+    cols: u16,
+    turn: u16,
+}
+
+/// Arguments of `commander::publish_map`, read one after another.
+#[derive(Serialize, Deserialize)]
+struct PublishBytes {
+    map: MoveMap,
+    positions: Vec<Vec<u8>>,
+}
+
+impl From<&Map> for MoveMap {
+    fn from(map: &Map) -> Self {
+        Self {
+            id: map.id,
+            grid: map.grid.clone(),
+            rows: map.rows() as u16,
+            cols: map.cols() as u16,
+            turn: map.turn,
+        }
+    }
+}
+
+impl TryFrom<MoveMap> for Map {
+    type Error = anyhow::Error;
+
+    /// Like Move, ignores the stored dimensions: presets such as `demo_1`
+    /// were encoded with values which don't match the grid. A grid with rows
+    /// of different length can't be played though.
+    fn try_from(map: MoveMap) -> Result<Self, Self::Error> {
+        let cols = map.grid.first().map_or(0, |row| row.len());
+        if cols == 0 || map.grid.iter().any(|row| row.len() != cols) {
+            return Err(anyhow::anyhow!("Map grid is empty or not rectangular"));
+        }
+
+        Ok(Map {
+            id: map.id,
+            grid: map.grid,
+            turn: map.turn,
+        })
+    }
 }
 
 impl Display for TileType {
@@ -62,3 +141,116 @@ impl Display for TileType {
         )
     }
 }
+
+#[test]
+fn test_map_to_bytes() {
+    let map = Map {
+        id: ID::default(),
+        grid: vec![vec![
+            Tile {
+                tile_type: TileType::Empty,
+                unit: None,
+            },
+            Tile {
+                tile_type: TileType::Cover {
+                    left: 1,
+                    top: 0,
+                    right: 0,
+                    bottom: 2,
+                },
+                unit: None,
+            },
+        ]],
+        turn: 3,
+    };
+
+    let bytes = map.to_bytes();
+
+    assert_eq!(&bytes[..32], &[0; 32]);
+    assert_eq!(
+        &bytes[32..],
+        &[
+            1, 2, // rows and columns of the grid vector
+            0, 0, // empty tile, no unit
+            1, 1, 0, 0, 2, 0, // cover tile, no unit
+            1, 0, 2, 0, // rows and cols as u16
+            3, 0, // turn
+        ]
+    );
+}
+
+/// Decode a hex string of the Move `x"..."` literals.
+#[cfg(test)]
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Replace the dimensions stored after the grid in the Move `Map` bytes. The
+/// presets in Move store them big-endian, `Map::to_bytes` writes valid ones.
+#[cfg(test)]
+fn with_dimensions(bytes: &[u8], rows: u16, cols: u16) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    let start = bytes.len() - 6;
+    bytes[start..start + 2].copy_from_slice(&rows.to_le_bytes());
+    bytes[start + 2..start + 4].copy_from_slice(&cols.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn test_map_from_bytes_demo_1() {
+    // `map::demo_1` in Move, prefixed with the zero ID.
+    let mut bytes = vec![0; 32];
+    bytes.extend(from_hex("070700000000010200000200010000000100010000000200000000000702000000000000000000000000000700000000000000000000010000000100010000000100070100010000000100010200000000020000000101000000000101000000000701000000010001000100020000000000000000000000070000000000000000000000000000070000020000000000010101000000010001000000010001010000000700070000"));
+
+    let map = Map::from_bytes(&bytes).unwrap();
+    assert_eq!(map.dimensions(), (7, 7));
+    assert_eq!(map.turn, 0);
+    assert_eq!(map.to_bytes(), with_dimensions(&bytes, 7, 7));
+
+    // First rows of the schema of the map in the Move docs.
+    let text = map.to_string();
+    let mut lines = text.lines();
+    assert_eq!(
+        lines.next(),
+        Some("|        |        |2002    |0001    |0002    |        |        |")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("|XXXX    |        |        |        |        |        |        |")
+    );
+
+    let positions = vec![vec![0, 3], vec![6, 5]];
+    let publish_bytes = map.to_publish_bytes(&positions);
+    assert_eq!(&publish_bytes[..bytes.len()], &map.to_bytes()[..]);
+
+    let (decoded, decoded_positions) = Map::from_publish_bytes(&publish_bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), map.to_bytes());
+    assert_eq!(decoded_positions, positions);
+}
+
+#[test]
+fn test_map_from_bytes_demo_2() {
+    // `map::demo_2` in Move.
+    let bytes = from_hex(
+        "00000000000000000000000000000000000000000000000000000000000000000a0a00000000000000000000000000000000000000000a00000101000001000100000001000100000101000000000001010000010001000000010001000001010000000a00000000000000000000000000000000000000000a00000200020002000000000002000200020000000a000001010000010000000100000101000000000000000200000000000a00000000000000000000000000000000000000000a00000000000000000000000000000000000000000a010202000000010001000000010001000000010002020000000000000101020000000100010000000100010000000100020200000a01020000000000000000010000010000000000000000000000000100000200000a010200000200010000000200010000000200010000000200010000000200010000000200010000000200010000000200010000000200010000020200000a000a0000",
+    );
+
+    let map = Map::from_bytes(&bytes).unwrap();
+    assert_eq!(map.dimensions(), (10, 10));
+    assert_eq!(map.to_bytes(), with_dimensions(&bytes, 10, 10));
+
+    let positions = vec![vec![8, 2], vec![7, 6], vec![1, 2], vec![1, 7]];
+    let (decoded, decoded_positions) =
+        Map::from_publish_bytes(&map.to_publish_bytes(&positions)).unwrap();
+    assert_eq!(decoded.to_bytes(), map.to_bytes());
+    assert_eq!(decoded_positions, positions);
+
+    // Trailing bytes and grids with rows of different length are rejected.
+    assert!(Map::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    let mut invalid = map.clone();
+    invalid.grid[3].pop();
+    assert!(Map::from_bytes(&invalid.to_bytes()).is_err());
+}
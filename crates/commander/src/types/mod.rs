@@ -3,16 +3,28 @@
 // SPDX-License-Identifier: MIT
 
 mod game_map;
+mod items;
+mod line_of_sight;
 mod map;
 mod path;
+mod pathfinding;
+mod replay_file;
 mod stats;
+mod text;
 mod unit;
+mod visibility;
 
 pub use game_map::*;
+pub use items::*;
+pub use line_of_sight::*;
 pub use map::*;
 pub use path::*;
+pub use pathfinding::*;
+pub use replay_file::*;
 pub use stats::*;
+pub use text::*;
 pub use unit::*;
+pub use visibility::*;
 
 use std::fmt::Display;
 
@@ -46,7 +58,7 @@ pub enum Record {
     Explosion,
     /// Effect: critical hit.
     CriticalHit(u8),
-    /// Header: action with effects.
+    /// Header: action with effects. Target position and the blast radius.
     Grenade(u16, u16, u16),
     /// Effect: unit KIA.
     UnitKIA(ID),
@@ -66,7 +78,7 @@ impl Display for Record {
             Record::Miss => write!(f, "Miss"),
             Record::Explosion => write!(f, "Explosion"),
             Record::CriticalHit(damage) => write!(f, "Critical Hit: {}", damage),
-            Record::Grenade(x, y, damage) => write!(f, "Grenade: ({}, {}) -> {}", x, y, damage),
+            Record::Grenade(x, y, radius) => write!(f, "Grenade: ({}, {}) r={}", x, y, radius),
             Record::UnitKIA(id) => write!(f, "Unit KIA: {}", id),
             Record::Dodged => write!(f, "Dodged"),
         }
@@ -178,6 +190,17 @@ pub struct Game {
     pub recruits: (Address, u64),
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An open invitation to join a `Game`, owned by the `Commander` object until
+/// another player joins.
+pub struct Host {
+    pub id: ID,
+    pub game_id: ID,
+    pub name: String,
+    pub timestamp_ms: u64,
+    pub host: Address,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Commander {
     pub id: ID,
@@ -193,6 +216,50 @@ pub struct Replay {
     pub history: History,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+/// Recruits who took part in a game, used to tell units apart in its `Replay`.
+/// Units KIA are identified by the `UnitKIA` records, the ones who survived by
+/// their position at the end of the game.
+pub struct Roster {
+    pub recruits: Vec<Recruit>,
+    /// Positions of the units alive at the end of the game and their IDs.
+    pub survivors: Vec<((u8, u8), ID)>,
+}
+
+impl Roster {
+    /// Create a `Roster` from the final state of the `Game`.
+    pub fn from_game(game: &Game, recruits: Vec<Recruit>) -> Self {
+        let survivors = game
+            .map
+            .grid
+            .iter()
+            .enumerate()
+            .flat_map(|(x, row)| {
+                row.iter().enumerate().filter_map(move |(y, tile)| {
+                    tile.unit.map(|unit| ((x as u8, y as u8), unit.recruit))
+                })
+            })
+            .collect();
+
+        Self {
+            recruits,
+            survivors,
+        }
+    }
+
+    pub fn recruit(&self, id: &ID) -> Option<&Recruit> {
+        self.recruits.iter().find(|r| r.id == *id)
+    }
+
+    /// ID of the unit which ended the game at the position.
+    pub fn survivor(&self, position: (u8, u8)) -> Option<ID> {
+        self.survivors
+            .iter()
+            .find(|(p, _)| *p == position)
+            .map(|(_, id)| *id)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
@@ -212,6 +279,16 @@ impl Direction {
             Direction::None => Direction::Up, // goes to Up and never happens again
         };
     }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::None => Direction::None,
+        }
+    }
 }
 
 impl Into<u8> for Direction {
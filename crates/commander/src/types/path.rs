@@ -34,6 +34,15 @@ impl GridPath {
         Self(path)
     }
 
+    /// Split the path after `steps` steps. The tile at the split ends the
+    /// first path and starts the second one.
+    pub fn split_at(&self, steps: usize) -> (Self, Self) {
+        (
+            Self(self.0[..=steps].to_vec()),
+            Self(self.0[steps..].to_vec()),
+        )
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
@@ -0,0 +1,266 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Pathfinding on the `GameMap`. A `DistanceField` is computed once for a unit
+//! with Dijkstra's algorithm and then answers both "which tiles can the unit
+//! reach" and "what is the shortest path to this tile" without searching again.
+//!
+//! Movement follows `map::check_path` in Move: units, obstacles and high cover
+//! block the way, low cover doesn't.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use super::{Direction, GameMap, TileType};
+
+/// Cover of this level blocks movement through the tile side.
+pub const HIGH_COVER: u8 = 2;
+
+/// Costs and shortest paths from the `origin` to every reachable tile.
+#[derive(Debug, Clone)]
+pub struct DistanceField {
+    origin: (u8, u8),
+    /// Total cost to reach the tile, `None` if it is not reachable.
+    costs: Vec<Vec<Option<u16>>>,
+    /// The tile the shortest path comes from.
+    previous: Vec<Vec<Option<(u8, u8)>>>,
+}
+
+impl DistanceField {
+    /// Compute the field with every step costing 1, so the cost of a tile is
+    /// the length of the path. Tiles costing more than `limit` are ignored.
+    pub fn new(map: &GameMap, origin: (u8, u8), limit: Option<u16>) -> Self {
+        Self::with_costs(map, origin, limit, step_cost)
+    }
+
+    /// Compute the field with custom step costs. The `cost` of moving between
+    /// adjacent tiles is `None` if the step is not allowed.
+    pub fn with_costs(
+        map: &GameMap,
+        origin: (u8, u8),
+        limit: Option<u16>,
+        cost: impl Fn(&GameMap, (u8, u8), (u8, u8)) -> Option<u16>,
+    ) -> Self {
+        let rows = map.grid.len();
+        let cols = map.grid.first().map_or(0, |row| row.len());
+        let mut field = Self {
+            origin,
+            costs: vec![vec![None; cols]; rows],
+            previous: vec![vec![None; cols]; rows],
+        };
+
+        if !field.contains(origin) {
+            return field;
+        }
+
+        let mut queue = BinaryHeap::new();
+        field.costs[origin.0 as usize][origin.1 as usize] = Some(0);
+        queue.push(Reverse((0, origin)));
+
+        while let Some(Reverse((total, position))) = queue.pop() {
+            // Already reached with a lower cost.
+            if field.cost(position).is_some_and(|c| c < total) {
+                continue;
+            }
+
+            for next in neighbours(map, position) {
+                let Some(step) = cost(map, position, next) else {
+                    continue;
+                };
+
+                let total = total + step;
+                if limit.is_some_and(|limit| total > limit)
+                    || field.cost(next).is_some_and(|c| c <= total)
+                {
+                    continue;
+                }
+
+                field.costs[next.0 as usize][next.1 as usize] = Some(total);
+                field.previous[next.0 as usize][next.1 as usize] = Some(position);
+                queue.push(Reverse((total, next)));
+            }
+        }
+
+        field
+    }
+
+    pub fn origin(&self) -> (u8, u8) {
+        self.origin
+    }
+
+    /// Cost of the shortest path to the tile, `None` if it is not reachable.
+    pub fn cost(&self, (x, y): (u8, u8)) -> Option<u16> {
+        *self.costs.get(x as usize)?.get(y as usize)?
+    }
+
+    /// All reachable tiles except the origin.
+    pub fn reachable(&self) -> Vec<(u8, u8)> {
+        (0..self.costs.len() as u8)
+            .flat_map(|x| (0..self.costs[x as usize].len() as u8).map(move |y| (x, y)))
+            .filter(|position| *position != self.origin && self.cost(*position).is_some())
+            .collect()
+    }
+
+    /// Reachable tiles, except the origin, which cost at most `limit`.
+    pub fn within(&self, limit: u16) -> Vec<(u8, u8)> {
+        self.reachable()
+            .into_iter()
+            .filter(|position| self.cost(*position).is_some_and(|c| c <= limit))
+            .collect()
+    }
+
+    /// Shortest path from the origin to the `target`, both included.
+    pub fn path(&self, target: (u8, u8)) -> Option<Vec<(u8, u8)>> {
+        self.cost(target)?;
+
+        let mut path = vec![target];
+        let mut position = target;
+
+        while let Some(previous) = self.previous[position.0 as usize][position.1 as usize] {
+            path.push(previous);
+            position = previous;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    fn contains(&self, (x, y): (u8, u8)) -> bool {
+        self.costs
+            .get(x as usize)
+            .is_some_and(|row| (y as usize) < row.len())
+    }
+}
+
+/// AP needed to walk `distance` tiles. Like `unit::perform_move` in Move, a
+/// single move costs 1 AP and is limited by the unit's `mobility`, so longer
+/// distances take several moves, e.g. a dash is 2 moves in a row.
+pub fn move_ap_cost(distance: u16, mobility: u16) -> Option<u16> {
+    match (distance, mobility) {
+        (0, _) | (_, 0) => None,
+        (distance, mobility) => Some(distance.div_ceil(mobility)),
+    }
+}
+
+/// Default cost of a step: 1 if the step is allowed.
+pub fn step_cost(map: &GameMap, from: (u8, u8), to: (u8, u8)) -> Option<u16> {
+    can_step(map, from, to).then_some(1)
+}
+
+/// Check that a unit can step from a tile to the adjacent one. Same rules as
+/// `map::check_path` in Move.
+pub fn can_step(map: &GameMap, from: (u8, u8), to: (u8, u8)) -> bool {
+    tile(map, to).is_some_and(|target| target.unit.is_none()) && can_pass(map, from, to)
+}
+
+/// Same as `can_step`, but only the terrain is checked, units on the way are
+/// ignored.
+pub fn can_pass(map: &GameMap, from: (u8, u8), to: (u8, u8)) -> bool {
+    let (Some(source), Some(target)) = (tile(map, from), tile(map, to)) else {
+        return false;
+    };
+
+    let direction = Direction::from_coords(from, to);
+    if direction == Direction::None {
+        return false;
+    }
+
+    !blocks(&source.tile_type, direction) && !blocks(&target.tile_type, direction.opposite())
+}
+
+/// Whether the side of the tile facing the `direction` blocks movement.
+pub(super) fn blocks(tile_type: &TileType, direction: Direction) -> bool {
+    match *tile_type {
+        TileType::Empty => false,
+        TileType::Obstacle => true,
+        TileType::Cover {
+            left,
+            top,
+            right,
+            bottom,
+        } => match direction {
+            Direction::Left => left == HIGH_COVER,
+            Direction::Right => right == HIGH_COVER,
+            Direction::Up => top == HIGH_COVER,
+            Direction::Down => bottom == HIGH_COVER,
+            Direction::None => false,
+        },
+    }
+}
+
+pub(super) fn tile(map: &GameMap, (x, y): (u8, u8)) -> Option<&super::GameTile> {
+    map.grid.get(x as usize)?.get(y as usize)
+}
+
+/// Adjacent tiles within the grid.
+fn neighbours(map: &GameMap, (x, y): (u8, u8)) -> Vec<(u8, u8)> {
+    let mut points = Vec::new();
+
+    if tile(map, (x + 1, y)).is_some() {
+        points.push((x + 1, y));
+    }
+
+    if tile(map, (x, y + 1)).is_some() {
+        points.push((x, y + 1));
+    }
+
+    if x > 0 {
+        points.push((x - 1, y));
+    }
+
+    if y > 0 {
+        points.push((x, y - 1));
+    }
+
+    points
+}
+
+#[test]
+fn test_distance_field() {
+    use super::Unit;
+    use std::{cell::RefCell, rc::Rc};
+
+    // . . # .
+    // . U ] .
+    // . . . .
+    let mut map = GameMap::new(4, 3);
+    map.grid[0][2].tile_type = TileType::Obstacle;
+    map.grid[1][1].unit = Some(Rc::new(RefCell::new(Unit::default())));
+    map.grid[1][2].tile_type = TileType::Cover {
+        left: HIGH_COVER,
+        top: 0,
+        right: 1,
+        bottom: 0,
+    };
+
+    let field = DistanceField::new(&map, (0, 0), None);
+    assert_eq!(field.cost((0, 0)), Some(0));
+    assert_eq!(field.cost((0, 2)), None); // obstacle
+    assert_eq!(field.cost((1, 1)), None); // unit
+    assert!(can_pass(&map, (0, 1), (1, 1)));
+    assert_eq!(field.cost((0, 3)), Some(7)); // around the high cover
+    assert_eq!(
+        field.path((1, 2)),
+        Some(vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2)])
+    );
+
+    // Low cover on the right doesn't block the way to (1, 3).
+    assert_eq!(field.path((1, 3)).map(|p| p.len()), Some(7));
+    assert_eq!(field.reachable().len(), 9);
+    assert_eq!(field.within(2), vec![(0, 1), (1, 0), (2, 0)]);
+
+    assert_eq!(move_ap_cost(0, 4), None);
+    assert_eq!(move_ap_cost(4, 4), Some(1));
+    assert_eq!(move_ap_cost(5, 4), Some(2));
+    assert_eq!(move_ap_cost(3, 0), None);
+
+    let field = DistanceField::new(&map, (0, 0), Some(2));
+    assert_eq!(field.reachable(), vec![(0, 1), (1, 0), (2, 0)]);
+    assert_eq!(field.path((2, 1)), None);
+
+    // Custom costs: stepping into the bottom row is expensive.
+    let field = DistanceField::with_costs(&map, (0, 0), None, |map, from, to| {
+        step_cost(map, from, to).map(|cost| if to.0 == 2 { cost * 5 } else { cost })
+    });
+    assert_eq!(field.cost((2, 0)), Some(6));
+    assert_eq!(field.cost((1, 2)), Some(17));
+}
@@ -0,0 +1,186 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Self-contained replay files which can be played without the network. A file
+//! stores the `Replay` along with the `Preset` it was played on and the
+//! `Roster` of the game.
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Preset, Replay, Roster};
+
+/// Version of the replay file format, stored as the first field of the file.
+pub const REPLAY_FILE_VERSION: u8 = 1;
+
+/// Directory in the storage dir where replay files are exported.
+pub const REPLAYS_DIR: &str = "replays";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFile {
+    pub version: u8,
+    pub replay: Replay,
+    pub preset: Preset,
+    pub roster: Roster,
+}
+
+/// Encoding of a `ReplayFile`, defined by the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFormat {
+    Json,
+    Bcs,
+}
+
+impl ReplayFormat {
+    pub const ALL: [ReplayFormat; 2] = [ReplayFormat::Json, ReplayFormat::Bcs];
+
+    pub fn extension(&self) -> &str {
+        match self {
+            ReplayFormat::Json => "json",
+            ReplayFormat::Bcs => "bcs",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(ReplayFormat::Json),
+            "bcs" => Some(ReplayFormat::Bcs),
+            _ => None,
+        }
+    }
+}
+
+impl ReplayFile {
+    pub fn new(replay: Replay, preset: Preset, roster: Roster) -> Self {
+        Self {
+            version: REPLAY_FILE_VERSION,
+            replay,
+            preset,
+            roster,
+        }
+    }
+
+    pub fn to_bytes(&self, format: ReplayFormat) -> Result<Vec<u8>, anyhow::Error> {
+        match format {
+            ReplayFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+            ReplayFormat::Bcs => Ok(bcs::to_bytes(self)?),
+        }
+    }
+
+    /// Decode the file, fails if it was written by another version.
+    pub fn from_bytes(bytes: &[u8], format: ReplayFormat) -> Result<Self, anyhow::Error> {
+        let version = match format {
+            ReplayFormat::Json => serde_json::from_slice::<serde_json::Value>(bytes)?
+                .get("version")
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u8),
+            ReplayFormat::Bcs => bytes.first().copied(),
+        };
+
+        if version != Some(REPLAY_FILE_VERSION) {
+            return Err(anyhow::anyhow!(
+                "Unsupported replay file version: {:?}",
+                version
+            ));
+        }
+
+        match format {
+            ReplayFormat::Json => Ok(serde_json::from_slice(bytes)?),
+            ReplayFormat::Bcs => Ok(bcs::from_bytes(bytes)?),
+        }
+    }
+
+    /// Write the file in every format to the `dir`, named after the replay.
+    pub fn save(&self, dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+        fs::create_dir_all(dir)?;
+
+        ReplayFormat::ALL
+            .iter()
+            .map(|format| {
+                let path = dir.join(format!("{}.{}", self.replay.id.0, format.extension()));
+                fs::write(&path, self.to_bytes(*format)?)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let format = ReplayFormat::from_path(path)
+            .ok_or(anyhow::anyhow!("Unknown replay file: {}", path.display()))?;
+
+        Self::from_bytes(&fs::read(path)?, format)
+    }
+
+    /// List the replay files in the `dir`, sorted by name.
+    pub fn list(dir: &Path) -> Vec<PathBuf> {
+        let mut files = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| ReplayFormat::from_path(path).is_some())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        files.sort();
+        files
+    }
+}
+
+impl Display for ReplayFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayFormat::Json => write!(f, "JSON"),
+            ReplayFormat::Bcs => write!(f, "BCS"),
+        }
+    }
+}
+
+#[test]
+fn test_replay_file_formats() {
+    use super::{History, ID, Map, Record, Tile, TileType};
+
+    let file = ReplayFile::new(
+        Replay {
+            id: ID::default(),
+            preset_id: ID::default(),
+            history: History(vec![Record::RecruitPlaced(0, 0), Record::NextTurn(1)]),
+        },
+        Preset {
+            id: ID::default(),
+            map: Map {
+                id: ID::default(),
+                grid: vec![vec![
+                    Tile {
+                        tile_type: TileType::Empty,
+                        unit: None,
+                    };
+                    2
+                ]],
+                turn: 0,
+            },
+            name: "Test".to_string(),
+            positions: vec![vec![0, 0]],
+            author: ID::default().0,
+            popularity: 0,
+        },
+        Roster::default(),
+    );
+
+    for format in ReplayFormat::ALL {
+        let bytes = file.to_bytes(format).unwrap();
+        let decoded = ReplayFile::from_bytes(&bytes, format).unwrap();
+        assert_eq!(decoded.replay.history, file.replay.history);
+        assert_eq!(decoded.preset.name, "Test");
+
+        let mut outdated = file.clone();
+        outdated.version = 0;
+        let bytes = outdated.to_bytes(format).unwrap();
+        assert!(ReplayFile::from_bytes(&bytes, format).is_err());
+    }
+}
@@ -75,12 +75,84 @@ impl Stats {
     pub fn inner(&self) -> u128 {
         self.0
     }
+
+    /// Create `Stats` from the raw bit field value.
+    pub fn new_unchecked(value: u128) -> Self {
+        Self(value)
+    }
+
+    /// Default stats of a Weapon, used when a Recruit has no weapon equipped.
+    pub fn default_weapon() -> Self {
+        Self(pack_u8(vec![
+            0, 0, 0, 0, 0, 0, 4, 2, 0, 0, 1, 1, 0, 4, 3, 0,
+        ]))
+    }
+
+    /// Default stats of an Armor, used when a Recruit has no armor equipped.
+    pub fn default_armor() -> Self {
+        Self(0)
+    }
+
+    /// Negate the modifier values, so that applying the result reverts the
+    /// original modifier. Mirrors `stats::negate` in Move.
+    pub fn negate(&self) -> Stats {
+        let values = unpack_u8(self.0)
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| match i {
+                _ if i >= NUM_PARAMS => value,
+                _ if value > SIGN_VALUE => value - SIGN_VALUE,
+                _ => value + SIGN_VALUE,
+            })
+            .collect();
+
+        Stats(pack_u8(values))
+    }
+
+    /// Apply the `modifier` to the stats. Mirrors `stats::add` in Move: values
+    /// of the modifier are signed (the first bit is the sign), the result never
+    /// underflows and is capped at 127.
+    pub fn add(&self, modifier: &Stats) -> Stats {
+        let values = unpack_u8(self.0);
+        let modifiers = unpack_u8(modifier.0);
+
+        let values = values
+            .into_iter()
+            .zip(modifiers)
+            .enumerate()
+            .map(|(i, (value, modifier))| {
+                // the last byte (version) is not modified
+                if i >= NUM_PARAMS || modifier == 0 || modifier == SIGN_VALUE {
+                    return value;
+                }
+
+                let value = if modifier > SIGN_VALUE {
+                    value - (modifier - SIGN_VALUE).min(value)
+                } else {
+                    value.saturating_add(modifier)
+                };
+
+                value.min(SIGN_VALUE - 1)
+            })
+            .collect();
+
+        Stats(pack_u8(values))
+    }
 }
 
+/// Number of stats in the bit field, the rest is reserved.
+const NUM_PARAMS: usize = 15;
+/// Sign bit of a single stat value.
+const SIGN_VALUE: u8 = 0x80;
+
 fn read_u8_at_offset_be(value: u128, offset: u8) -> i8 {
     (value >> 8 * offset & 0xFF) as i8
 }
 
+fn unpack_u8(value: u128) -> Vec<u8> {
+    (0..16).map(|i| (value >> (8 * i) & 0xFF) as u8).collect()
+}
+
 fn pack_u8(values: Vec<u8>) -> u128 {
     values.iter().enumerate().fold(0, |acc, (index, &value)| {
         acc | (value as u128) << (8 * index)
@@ -141,6 +213,42 @@ fn test_stats() {
     assert_eq!(weapon_stats.ammo(), 3);
 }
 
+#[test]
+fn test_stats_add() {
+    let stats = Stats::default().add(&Stats::default_weapon());
+
+    assert_eq!(stats.mobility(), 7);
+    assert_eq!(stats.aim(), 65);
+    assert_eq!(stats.health(), 10);
+    assert_eq!(stats.damage(), 4);
+    assert_eq!(stats.range(), 4);
+    assert_eq!(stats.ammo(), 3);
+
+    // negative modifiers (-5 aim, -20 health) never underflow
+    let modifier = Stats(pack_u8(vec![
+        0,
+        0x80 + 5,
+        0x80 + 20,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+    ]));
+    let stats = stats.add(&modifier);
+
+    assert_eq!(stats.aim(), 60);
+    assert_eq!(stats.health(), 0);
+}
+
 #[test]
 fn test_in_game_stats() {
     let stats = Stats(15658020524713025353488063875662087);
@@ -0,0 +1,217 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Text rendering of the `Map` and the `GameMap` for logs, tests and the CLI.
+//! Every row of the grid is a line of `|`-separated cells, and every cell is
+//! `CELL_WIDTH` wide:
+//!
+//! - 4 chars of the tile: blank for `Empty`, `XXXX` for `Obstacle` and the
+//!   left, top, right and bottom cover values for `Cover`;
+//! - 1 char of the overlay marker, blank if the tile is not marked;
+//! - 3 chars of the unit: `U` followed by its HP, blank if there's no unit.
+//!
+//! Rows are the `x` coordinate, columns are `y`.
+
+use std::{collections::HashMap, fmt::Display};
+
+use super::{GameMap, GridPath, Map, TileType, Unit};
+
+/// Width of a single cell, without the separator.
+pub const CELL_WIDTH: usize = 8;
+
+/// Marker of the tiles in a path overlay.
+pub const PATH_MARKER: char = '*';
+
+/// Marker of the highlighted tiles.
+pub const HIGHLIGHT_MARKER: char = '!';
+
+/// Text representation of a grid, created with `Map::to_text` or
+/// `GameMap::to_text`. Overlays are added with the builder methods and the
+/// result is printed through `Display`.
+///
+/// ```ignore
+/// println!("{}", map.to_text().path(&path).coordinates(true));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextMap {
+    grid: Vec<Vec<(TileType, Option<Unit>)>>,
+    markers: HashMap<(u8, u8), char>,
+    coordinates: bool,
+}
+
+impl TextMap {
+    pub fn new(grid: Vec<Vec<(TileType, Option<Unit>)>>) -> Self {
+        Self {
+            grid,
+            markers: HashMap::new(),
+            coordinates: false,
+        }
+    }
+
+    /// Mark the tiles with the `marker`. Later overlays replace the markers of
+    /// earlier ones, tiles outside of the grid are ignored.
+    pub fn overlay(mut self, tiles: &[(u8, u8)], marker: char) -> Self {
+        self.markers
+            .extend(tiles.iter().map(|position| (*position, marker)));
+        self
+    }
+
+    /// Mark the tiles of the path with the `PATH_MARKER`.
+    pub fn path(self, path: &GridPath) -> Self {
+        self.overlay(&path.0, PATH_MARKER)
+    }
+
+    /// Mark the tiles with the `HIGHLIGHT_MARKER`.
+    pub fn highlight(self, tiles: &[(u8, u8)]) -> Self {
+        self.overlay(tiles, HIGHLIGHT_MARKER)
+    }
+
+    /// Print the indices of rows and columns along the grid.
+    pub fn coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    fn cell(&self, position: (u8, u8), tile_type: &TileType, unit: &Option<Unit>) -> String {
+        let tile = match tile_type {
+            TileType::Empty => "    ".to_string(),
+            TileType::Obstacle => "XXXX".to_string(),
+            TileType::Cover {
+                left,
+                top,
+                right,
+                bottom,
+            } => format!("{}{}{}{}", left, top, right, bottom),
+        };
+
+        let marker = self.markers.get(&position).copied().unwrap_or(' ');
+        let unit = match unit {
+            Some(unit) => format!("U{:<2}", unit.hp.value()),
+            None => "   ".to_string(),
+        };
+
+        format!("{}{}{}", tile, marker, unit)
+    }
+}
+
+impl Display for TextMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.coordinates {
+            let cols = self.grid.first().map_or(0, |row| row.len());
+            write!(f, "    ")?;
+            for y in 0..cols {
+                write!(f, "|{:^width$}", y, width = CELL_WIDTH)?;
+            }
+            writeln!(f, "|")?;
+        }
+
+        for (x, row) in self.grid.iter().enumerate() {
+            if self.coordinates {
+                write!(f, "{:>3} ", x)?;
+            }
+
+            for (y, (tile_type, unit)) in row.iter().enumerate() {
+                write!(f, "|{}", self.cell((x as u8, y as u8), tile_type, unit))?;
+            }
+
+            writeln!(f, "|")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Map {
+    pub fn to_text(&self) -> TextMap {
+        TextMap::new(
+            self.grid
+                .iter()
+                .map(|row| row.iter().map(|t| (t.tile_type, t.unit)).collect())
+                .collect(),
+        )
+    }
+}
+
+impl GameMap {
+    pub fn to_text(&self) -> TextMap {
+        TextMap::new(
+            self.grid
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|t| (t.tile_type, t.unit.as_ref().map(|u| *u.borrow())))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+impl Display for GameMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+#[test]
+fn test_text_map() {
+    use super::{ID, Param, Tile};
+
+    let tile = |tile_type, unit| Tile { tile_type, unit };
+    let unit = Unit {
+        hp: Param::new(7, 10),
+        ..Default::default()
+    };
+
+    let map = Map {
+        id: ID::default(),
+        grid: vec![
+            vec![
+                tile(TileType::Empty, Some(unit)),
+                tile(TileType::Empty, None),
+                tile(TileType::Obstacle, None),
+            ],
+            vec![
+                tile(
+                    TileType::Cover {
+                        left: 0,
+                        top: 1,
+                        right: 2,
+                        bottom: 0,
+                    },
+                    Some(Unit::default()),
+                ),
+                tile(TileType::Empty, None),
+                tile(TileType::Empty, None),
+            ],
+        ],
+        turn: 0,
+    };
+
+    assert_eq!(
+        map.to_string(),
+        "|     U7 |        |XXXX    |\n\
+         |0120 U8 |        |        |\n"
+    );
+
+    let game_map = GameMap::from(map.clone());
+    assert_eq!(game_map.to_string(), map.to_string());
+
+    let path = GridPath(vec![(0, 0), (0, 1), (1, 1), (1, 2)]);
+    assert_eq!(
+        map.to_text()
+            .path(&path)
+            .highlight(&[(1, 2)])
+            .coordinates(true)
+            .to_string(),
+        "    |   0    |   1    |   2    |\n  \
+           0 |    *U7 |    *   |XXXX    |\n  \
+           1 |0120 U8 |    *   |    !   |\n"
+    );
+}
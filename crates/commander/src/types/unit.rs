@@ -20,6 +20,23 @@ pub struct Unit {
     pub last_turn: u16,
 }
 
+impl Unit {
+    /// Create a new `Unit` with the given (combined) stats. Mirrors the
+    /// `unit::from_recruit` function in Move, stats are expected to already
+    /// include the weapon and armor modifiers.
+    pub fn from_stats(recruit: ID, stats: Stats) -> Self {
+        Self {
+            recruit,
+            ap: Param::new(2, 2),
+            hp: Param::new(stats.health() as u16, stats.health() as u16),
+            ammo: Param::new(stats.ammo() as u16, stats.ammo() as u16),
+            grenade_used: false,
+            stats,
+            last_turn: 0,
+        }
+    }
+}
+
 impl Default for Unit {
     fn default() -> Self {
         Self {
@@ -0,0 +1,125 @@
+// Copyright (c) Sui Potatoes
+// SPDX-License-Identifier: MIT
+
+//! Fog of war. Every player sees the tiles within the `SIGHT_RANGE` of their
+//! units which are also in the units' line of sight. The terrain of the tiles
+//! seen before is remembered as it was when they were last in sight, changes
+//! out of sight and the units on them are not shown.
+//!
+//! Like the line of sight, the visibility is not checked by the contract, the
+//! whole map is public on chain. It only hides what the squad can't see.
+
+use super::{GameMap, TileType, line_of_sight};
+
+/// How far the units see, in tiles.
+pub const SIGHT_RANGE: u8 = 8;
+
+/// Tiles seen by the units of a single player.
+#[derive(Debug, Clone, Default)]
+pub struct Visibility {
+    /// Tiles in sight of the units right now.
+    visible: Vec<Vec<bool>>,
+    /// Terrain of the tiles as it was when they were last in sight, `None`
+    /// if they have never been.
+    explored: Vec<Vec<Option<TileType>>>,
+}
+
+impl Visibility {
+    /// Visibility of the player with units at the `positions`, nothing has
+    /// been explored before.
+    pub fn new(map: &GameMap, positions: &[(u8, u8)]) -> Self {
+        let rows = map.grid.len();
+        let cols = map.grid.first().map_or(0, |row| row.len());
+        let mut visibility = Self {
+            visible: vec![vec![false; cols]; rows],
+            explored: vec![vec![None; cols]; rows],
+        };
+
+        visibility.update(map, positions);
+        visibility
+    }
+
+    /// Recompute the visible tiles after the units at the `positions` moved
+    /// and remember their terrain. Explored tiles stay explored.
+    pub fn update(&mut self, map: &GameMap, positions: &[(u8, u8)]) {
+        for (x, row) in self.visible.iter_mut().enumerate() {
+            for (y, visible) in row.iter_mut().enumerate() {
+                let tile = (x as u8, y as u8);
+
+                *visible = positions.iter().any(|unit| {
+                    map.manhattan_distance(*unit, tile) <= SIGHT_RANGE
+                        && line_of_sight(map, *unit, tile)
+                });
+
+                if *visible {
+                    self.explored[x][y] = Some(map.grid[x][y].tile_type);
+                }
+            }
+        }
+    }
+
+    /// Whether the tile is in sight of any of the units.
+    pub fn is_visible(&self, (x, y): (u8, u8)) -> bool {
+        self.visible
+            .get(x as usize)
+            .and_then(|row| row.get(y as usize))
+            .is_some_and(|visible| *visible)
+    }
+
+    /// Whether the tile has been in sight at some point.
+    pub fn is_explored(&self, position: (u8, u8)) -> bool {
+        self.remembered(position).is_some()
+    }
+
+    /// Terrain of the tile as it was when it was last in sight, it may have
+    /// changed since if the tile is not visible.
+    pub fn remembered(&self, (x, y): (u8, u8)) -> Option<TileType> {
+        *self.explored.get(x as usize)?.get(y as usize)?
+    }
+}
+
+#[test]
+fn test_visibility() {
+    use super::HIGH_COVER;
+
+    let mut map = GameMap::new(12, 3);
+    map.grid[0][2].tile_type = TileType::Obstacle;
+
+    let mut visibility = Visibility::new(&map, &[(0, 0)]);
+    assert!(visibility.is_visible((0, 0)));
+    assert!(visibility.is_visible((0, 2))); // the obstacle itself is seen
+    assert!(!visibility.is_visible((0, 3))); // behind the obstacle
+    assert!(visibility.is_visible((2, 6)));
+    assert!(!visibility.is_visible((2, 7))); // out of range
+    assert!(!visibility.is_visible((0, 20))); // off the grid
+
+    // The unit moves away, the tiles it saw are remembered.
+    visibility.update(&map, &[(0, 5)]);
+    assert!(!visibility.is_visible((0, 0)));
+    assert!(visibility.is_explored((0, 0)));
+    assert!(visibility.is_visible((0, 3)));
+
+    // Cover destroyed out of sight is remembered as it was.
+    map.grid[2][0].tile_type = TileType::Cover {
+        left: HIGH_COVER,
+        top: 0,
+        right: 0,
+        bottom: 0,
+    };
+    visibility.update(&map, &[(2, 1)]);
+    map.grid[2][0].tile_type = TileType::Empty;
+    visibility.update(&map, &[(0, 11)]);
+    assert!(!visibility.is_visible((2, 0)));
+    assert!(matches!(
+        visibility.remembered((2, 0)),
+        Some(TileType::Cover {
+            left: HIGH_COVER,
+            ..
+        })
+    ));
+
+    // No units, nothing is visible.
+    visibility.update(&map, &[]);
+    assert!(!visibility.is_visible((0, 3)));
+    assert!(visibility.is_explored((0, 3)));
+}